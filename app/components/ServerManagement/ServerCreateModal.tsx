@@ -1,5 +1,5 @@
 import { AnimatePresence, motion } from "framer-motion";
-import { useEffect, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import { SelectMenu } from "../misc/SelectMenu";
 import DiscreteSlider from "../misc/Slider";
 import { useSupportedLoaders } from "@/app/hooks/useSupportedLoaders";
@@ -7,7 +7,7 @@ import { useAtomValue } from "jotai";
 import { isMacAtom } from "@/app/atoms";
 import { RadioSelect } from "../misc/RadioSelect";
 import { IoCloseCircle } from "react-icons/io5";
-import { createServer } from "@/app/utils/server/createServer";
+import { cancelServerCreation, createServer } from "@/app/utils/server/createServer";
 import { notifyError, notifySuccess } from "@/app/utils/alerts";
 import { isValidInstanceName } from "@/app/utils/regexValidator";
 import { LoaderRenderer } from "../misc/Loader";
@@ -47,6 +47,7 @@ export const ServerCreateModal = ({
     const [loadingLoaders, setLoadingLoaders] = useState(false);
     const [ramGB, setRamGB] = useState<number>(2);
     const [loading, setLoading] = useState(false);
+    const jobIdRef = useRef<string | null>(null);
 
     useEffect(() => {
         function convertVersionList() {
@@ -110,11 +111,14 @@ export const ServerCreateModal = ({
                 return;
             }
 
+            jobIdRef.current = crypto.randomUUID();
+
             await createServer({
                 name: instanceName!,
                 version: instanceVersion!,
                 loader: selectedLoader!,
-                ramGb: ramGB!
+                ramGb: ramGB!,
+                jobId: jobIdRef.current
             });
 
             await refreshServers();
@@ -126,9 +130,14 @@ export const ServerCreateModal = ({
                 hideProgressBar: false
             });
         } catch (err) {
+            if (err === "Server creation cancelled") {
+                return;
+            }
+
             notifyError("An error occured while creating the server. Please try again!");
             console.error("Create server failed:", err);
         } finally {
+            jobIdRef.current = null;
             setLoading(false);
         }
     }
@@ -161,6 +170,11 @@ export const ServerCreateModal = ({
                     title="Close"
                     onClick={(e) => {
                         e.preventDefault();
+
+                        if (jobIdRef.current) {
+                            cancelServerCreation(jobIdRef.current).catch(console.error);
+                        }
+
                         setIsOpen(false);
                     }} 
                 />
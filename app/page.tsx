@@ -30,8 +30,8 @@ export default function Home() {
     useEffect(() => {
         async function getVersions() {
             try {
-                const data = await invoke<string[]>('get_mc_versions');
-                setServerVersions(data ?? null);
+                const data = await invoke<{ id: string }[]>('get_mc_versions');
+                setServerVersions(data?.map(v => v.id) ?? null);
             } catch(err) {
                 console.error(err);
             }
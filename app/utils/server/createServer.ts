@@ -6,13 +6,15 @@ type CreateServerInput = {
     version: string;
     loader: LoaderType;
     ramGb: number;
+    jobId: string;
 }
 
 export async function createServer({
     name,
     version,
     loader,
-    ramGb
+    ramGb,
+    jobId
 }: CreateServerInput) {
     if (!name) {
         throw new Error("Server Instance Name Is Required!");
@@ -34,6 +36,11 @@ export async function createServer({
         name,
         version,
         loader,
-        ramGb
+        ramGb,
+        jobId
     });
 }
+
+export async function cancelServerCreation(jobId: string) {
+    await invoke('cancel_server_creation', { jobId });
+}
@@ -0,0 +1,286 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::utils::download::{download_file, verify_checksum, Checksum, DownloadObserver};
+use crate::utils::path::cache_dir;
+
+/// Shared artifact cache
+///
+/// Server jars, loader installers and Java archives are stored once under
+/// `cache_dir()` and copied into each server. Entries with a known checksum are
+/// addressed by that hash, everything else by a hash of its URL. The checksum
+/// of a hash-addressed entry is also recorded under its URL, so the entry is
+/// still found offline when the checksum can't be fetched. Libraries
+/// fetched by the Fabric and Forge installers are kept under `libraries/` in
+/// their Maven layout and hard-linked into new servers before the installer
+/// runs, so each library is stored once however many servers use it.
+
+// Subdirectories of `cache_dir()` holding artifacts, the rest (manifests) isn't pruned here
+const ARTIFACT_DIRS: &[&str] = &["sha1", "sha256", "url", "libraries"];
+
+// A `.part` file touched this recently may belong to a download still running
+const PART_GRACE: Duration = Duration::from_secs(60 * 60);
+
+// Callers after the same entry share its `.part`, so one downloads while the
+// others wait and then find it cached
+static ENTRY_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn entry_lock(entry: &Path) -> Arc<tokio::sync::Mutex<()>> {
+    ENTRY_LOCKS
+        .lock()
+        .unwrap()
+        .entry(entry.to_path_buf())
+        .or_default()
+        .clone()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub files: u64,
+}
+
+fn cache_entry(base: &Path, url: &str, checksum: Option<&Checksum>) -> Result<PathBuf, String> {
+    let (kind, hash) = match checksum {
+        Some(Checksum::Sha1(h)) => ("sha1", h.trim().to_lowercase()),
+        Some(Checksum::Sha256(h)) => ("sha256", h.trim().to_lowercase()),
+        None => (
+            "url",
+            Sha256::digest(url.as_bytes())
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        ),
+    };
+
+    // Checksums can come from untrusted modpacks, only well-formed hex becomes a path
+    let len = if kind == "sha1" { 40 } else { 64 };
+    if hash.len() != len || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid {} checksum for {}", kind, url));
+    }
+
+    // Two-character fan-out keeps directories small
+    Ok(base.join(kind).join(&hash[..2]).join(hash))
+}
+
+/// `<url entry>.checksum`, holding e.g. "sha1 <hex>"
+fn checksum_record(base: &Path, url: &str) -> Result<PathBuf, String> {
+    let entry = cache_entry(base, url, None)?;
+    Ok(entry.with_extension("checksum"))
+}
+
+fn recorded_checksum(base: &Path, url: &str) -> Option<Checksum> {
+    let text = fs::read_to_string(checksum_record(base, url).ok()?).ok()?;
+
+    match text.trim().split_once(' ')? {
+        ("sha1", hash) => Some(Checksum::Sha1(hash.to_string())),
+        ("sha256", hash) => Some(Checksum::Sha256(hash.to_string())),
+        _ => None,
+    }
+}
+
+fn record_checksum(base: &Path, url: &str, checksum: &Checksum) -> Result<(), String> {
+    let path = checksum_record(base, url)?;
+    let text = match checksum {
+        Checksum::Sha1(hash) => format!("sha1 {}", hash.trim()),
+        Checksum::Sha256(hash) => format!("sha256 {}", hash.trim()),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    fs::write(path, text).map_err(|e| e.to_string())
+}
+
+fn libraries_dir(base: &Path) -> PathBuf {
+    base.join("libraries")
+}
+
+/// Downloads `url` into the cache unless it's already there, then copies it to `dest`.
+/// Returns the size of the file.
+pub async fn cached_download(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    checksum: Option<&Checksum>,
+    observer: &dyn DownloadObserver,
+) -> Result<u64, String> {
+    let base = cache_dir();
+
+    // Offline the checksum often can't be fetched, use the one seen last time
+    let recorded = checksum.is_none().then(|| recorded_checksum(&base, url)).flatten();
+    let checksum = checksum.or(recorded.as_ref());
+
+    let entry = cache_entry(&base, url, checksum)?;
+    let lock = entry_lock(&entry);
+    let _guard = lock.lock().await;
+
+    // verify_checksum drops a corrupted entry so it's fetched again
+    let hit = entry.exists() && verify_checksum(&entry, checksum).is_ok();
+
+    if hit {
+        // Pruning goes by modification time, so mark the entry as used
+        if let Ok(file) = fs::File::options().write(true).open(&entry) {
+            file.set_modified(SystemTime::now()).ok();
+        }
+
+        let size = fs::metadata(&entry).map(|m| m.len()).unwrap_or(0);
+        observer.progress(size, Some(size));
+    } else {
+        download_file(client, url, &entry, checksum, observer).await?;
+    }
+
+    if let Some(checksum) = checksum {
+        // Also refreshes the record's modification time so it's pruned with the entry
+        if let Err(e) = record_checksum(&base, url, checksum) {
+            eprintln!("Failed to record checksum of {}: {}", url, e);
+        }
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    fs::copy(&entry, dest).map_err(|e| format!("Failed to copy {} from cache: {}", url, e))
+}
+
+/// Links every file under `from` to the same relative path under `to`
+/// unless it already exists there. Falls back to copying where hard links
+/// aren't possible, e.g. a server on another drive than the cache.
+fn link_missing(from: &Path, to: &Path) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(from) else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        let src = entry.path();
+        let dst = to.join(entry.file_name());
+
+        if src.is_dir() {
+            link_missing(&src, &dst)?;
+        } else if !dst.exists() && src.extension().map_or(true, |ext| ext != "part") {
+            fs::create_dir_all(to).map_err(|e| e.to_string())?;
+
+            if fs::hard_link(&src, &dst).is_err() {
+                fs::copy(&src, &dst).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Puts cached libraries into a server before its loader installer runs.
+/// The Fabric and Forge installers skip libraries that are already present
+/// and re-download any that fail their own checksum check.
+pub fn seed_libraries(server_path: &Path) {
+    if let Err(e) = link_missing(&libraries_dir(&cache_dir()), &server_path.join("libraries")) {
+        eprintln!("Failed to seed libraries from cache: {}", e);
+    }
+}
+
+/// Stores the libraries a loader installer fetched so the next server reuses them
+pub fn store_libraries(server_path: &Path) {
+    if let Err(e) = link_missing(&server_path.join("libraries"), &libraries_dir(&cache_dir())) {
+        eprintln!("Failed to store libraries in cache: {}", e);
+    }
+}
+
+/// Cache Size
+
+fn dir_usage(dir: &Path) -> (u64, u64) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (0, 0);
+    };
+
+    entries.flatten().fold((0, 0), |(size, files), entry| {
+        let path = entry.path();
+
+        if path.is_dir() {
+            let (s, f) = dir_usage(&path);
+            (size + s, files + f)
+        } else {
+            let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            (size + len, files + 1)
+        }
+    })
+}
+
+fn cache_info() -> CacheInfo {
+    let base = cache_dir();
+    let (size_bytes, files) = dir_usage(&base);
+
+    CacheInfo {
+        path: base.to_string_lossy().to_string(),
+        size_bytes,
+        files,
+    }
+}
+
+/// Removes files last used before `cutoff` (all files when None) and any
+/// directories left empty. Unfinished `.part` downloads go once they are
+/// stale, a recent one may still be written to.
+fn prune_dir(dir: &Path, cutoff: Option<SystemTime>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            prune_dir(&path, cutoff);
+            fs::remove_dir(&path).ok(); // only succeeds when empty
+            continue;
+        }
+
+        let modified = entry.metadata().and_then(|m| m.modified()).ok();
+        let older_than = |time: SystemTime| modified.map_or(true, |m| m < time);
+
+        let remove = if path.extension().is_some_and(|ext| ext == "part") {
+            older_than(SystemTime::now() - PART_GRACE)
+        } else {
+            cutoff.map_or(true, older_than)
+        };
+
+        if remove {
+            fs::remove_file(&path).ok();
+        }
+    }
+}
+
+/// Cache Commands
+
+#[tauri::command]
+pub fn get_cache_info() -> CacheInfo {
+    cache_info()
+}
+
+/// Deletes cached artifacts not used in the last `older_than_days` days,
+/// or every artifact when no age is given. Cached manifests are kept for
+/// offline use. Returns what's left.
+#[tauri::command]
+pub fn prune_cache(older_than_days: Option<u64>) -> CacheInfo {
+    let cutoff = older_than_days
+        .map(|days| SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60));
+
+    let base = cache_dir();
+    for dir in ARTIFACT_DIRS {
+        prune_dir(&base.join(dir), cutoff);
+    }
+
+    cache_info()
+}
@@ -0,0 +1,174 @@
+use std::{
+    path::Path,
+    process::Child,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::artifact_cache::cached_download;
+use crate::state::app_state::AppState;
+use crate::utils::download::{Checksum, DownloadObserver};
+
+/// SERVER CREATION JOBS
+///
+/// Every `create_server` and `upgrade_server` call runs as a job the frontend
+/// can follow through `create-progress` events and abort with `cancel_server_creation`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreatePhase {
+    BackingUp,
+    FetchingMetadata,
+    InstallingJava,
+    Downloading,
+    Installing,
+    WritingFiles,
+    UpgradingWorld,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateProgress {
+    pub job_id: String,
+    pub phase: CreatePhase,
+    pub downloaded: Option<u64>,
+    pub total: Option<u64>,
+    pub log: Option<String>,
+}
+
+pub const CANCELLED: &str = "Server creation cancelled";
+
+#[derive(Clone)]
+pub struct CreationJob {
+    pub id: String,
+    app: AppHandle,
+    phase: Arc<Mutex<CreatePhase>>,
+    cancelled: Arc<AtomicBool>,
+    installer: Arc<Mutex<Option<Child>>>,
+}
+
+impl CreationJob {
+    pub fn new(id: String, app: AppHandle) -> Self {
+        Self {
+            id,
+            app,
+            phase: Arc::new(Mutex::new(CreatePhase::FetchingMetadata)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            installer: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn emit(&self, downloaded: Option<u64>, total: Option<u64>, log: Option<String>) {
+        let _ = self.app.emit(
+            "create-progress",
+            CreateProgress {
+                job_id: self.id.clone(),
+                phase: *self.phase.lock().unwrap(),
+                downloaded,
+                total,
+                log,
+            },
+        );
+    }
+
+    pub fn set_phase(&self, phase: CreatePhase) {
+        *self.phase.lock().unwrap() = phase;
+        self.emit(None, None, None);
+    }
+
+    pub fn log(&self, line: String) {
+        self.emit(None, None, Some(line));
+    }
+
+    /// Bails out between steps once the job was cancelled
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err(CANCELLED.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+
+        if let Some(child) = self.installer.lock().unwrap().as_mut() {
+            child.kill().ok();
+        }
+    }
+
+    /// Hands a running installer to the job so cancelling can kill it.
+    /// Polls instead of blocking on wait() to keep the child reachable.
+    pub async fn wait_installer(&self, child: Child) -> Result<bool, String> {
+        *self.installer.lock().unwrap() = Some(child);
+
+        // Cancelled before the installer was registered
+        if self.is_cancelled() {
+            self.cancel();
+        }
+
+        loop {
+            let status = {
+                let mut slot = self.installer.lock().unwrap();
+                let child = slot.as_mut().ok_or("Installer process missing")?;
+                child.try_wait().map_err(|e| e.to_string())?
+            };
+
+            if let Some(status) = status {
+                self.installer.lock().unwrap().take();
+                self.check_cancelled()?;
+                return Ok(status.success());
+            }
+
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    /// Downloads through the shared artifact cache, reporting progress as
+    /// part of this job and stopping as soon as it's cancelled.
+    /// Returns the size of the downloaded file.
+    pub async fn download(
+        &self,
+        client: &Client,
+        url: &str,
+        dest: &Path,
+        checksum: Option<&Checksum>,
+    ) -> Result<u64, String> {
+        self.check_cancelled()?;
+        self.set_phase(CreatePhase::Downloading);
+
+        cached_download(client, url, dest, checksum, self).await
+    }
+}
+
+impl DownloadObserver for CreationJob {
+    fn progress(&self, downloaded: u64, total: Option<u64>) {
+        self.emit(Some(downloaded), total, None);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+#[tauri::command]
+pub fn cancel_server_creation(
+    job_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let jobs = state.creation_jobs.lock().unwrap();
+    let job = jobs.get(&job_id).ok_or("No such server creation in progress")?;
+
+    job.cancel();
+
+    Ok(())
+}
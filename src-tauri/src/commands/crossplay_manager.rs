@@ -0,0 +1,393 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::server_creation::LoaderType;
+use crate::commands::server_management::{find_server, server_port, ServerConfig};
+use crate::state::app_state::AppState;
+use crate::utils::network::{endpoint, http_client, Endpoint};
+use crate::utils::download::{download_file, Checksum, NoProgress};
+
+/// Geyser + Floodgate crossplay
+///
+/// Bedrock players join a Java server through Geyser, which translates the
+/// Bedrock protocol. Geyser runs inside the server where the loader allows it
+/// (Paper plugin, Fabric mod) and as a standalone proxy in front of it otherwise.
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CrossplayMode {
+    Plugin,
+    Mod,
+    Standalone,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CrossplayConfig {
+    pub enabled: bool,
+    pub mode: CrossplayMode,
+    pub bedrock_port: u16,
+}
+
+const DEFAULT_BEDROCK_PORT: u16 = 19132;
+
+pub fn crossplay_mode(loader: &LoaderType) -> Result<CrossplayMode, String> {
+    match loader {
+        LoaderType::Paper => Ok(CrossplayMode::Plugin),
+        LoaderType::Fabric => Ok(CrossplayMode::Mod),
+        // Geyser has no Forge build, vanilla has no plugin API
+        LoaderType::Vanilla | LoaderType::Forge | LoaderType::NeoForge => {
+            Ok(CrossplayMode::Standalone)
+        }
+        LoaderType::Bedrock => Err("Bedrock servers don't need crossplay".into()),
+    }
+}
+
+fn geyser_download_url(project: &str, platform: &str) -> String {
+    format!(
+        "{}/v2/projects/{}/versions/latest/builds/latest/downloads/{}",
+        endpoint(Endpoint::Geyser),
+        project,
+        platform
+    )
+}
+
+/// Geyser Paths
+
+fn geyser_standalone_dir(server_path: &Path) -> PathBuf {
+    server_path.join("geyser")
+}
+
+fn geyser_jar_path(server_path: &Path, mode: CrossplayMode) -> PathBuf {
+    match mode {
+        CrossplayMode::Plugin => server_path.join("plugins").join("Geyser-Spigot.jar"),
+        CrossplayMode::Mod => server_path.join("mods").join("Geyser-Fabric.jar"),
+        CrossplayMode::Standalone => {
+            geyser_standalone_dir(server_path).join("Geyser-Standalone.jar")
+        }
+    }
+}
+
+fn floodgate_jar_path(server_path: &Path, mode: CrossplayMode) -> Option<PathBuf> {
+    match mode {
+        CrossplayMode::Plugin => Some(server_path.join("plugins").join("floodgate-spigot.jar")),
+        CrossplayMode::Mod => Some(server_path.join("mods").join("floodgate-fabric.jar")),
+        // Floodgate needs to live inside the server it authenticates for
+        CrossplayMode::Standalone => None,
+    }
+}
+
+fn fabric_api_jar_path(server_path: &Path) -> PathBuf {
+    server_path.join("mods").join("fabric-api.jar")
+}
+
+fn geyser_config_path(server_path: &Path, mode: CrossplayMode) -> PathBuf {
+    match mode {
+        CrossplayMode::Plugin => server_path
+            .join("plugins")
+            .join("Geyser-Spigot")
+            .join("config.yml"),
+        CrossplayMode::Mod => server_path
+            .join("config")
+            .join("Geyser-Fabric")
+            .join("config.yml"),
+        CrossplayMode::Standalone => geyser_standalone_dir(server_path).join("config.yml"),
+    }
+}
+
+/// Installing Geyser
+
+// Build metadata lists the SHA-256 of every platform download
+async fn geyser_checksum(client: &Client, project: &str, platform: &str) -> Option<Checksum> {
+    let build: Value = client
+        .get(format!(
+            "{}/v2/projects/{}/versions/latest/builds/latest",
+            endpoint(Endpoint::Geyser),
+            project
+        ))
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+
+    build["downloads"][platform]["sha256"]
+        .as_str()
+        .map(|h| Checksum::Sha256(h.to_string()))
+}
+
+async fn download_geyser_artifact(
+    client: &Client,
+    project: &str,
+    platform: &str,
+    dest: &Path,
+) -> Result<(), String> {
+    let checksum = geyser_checksum(client, project, platform).await;
+
+    download_file(
+        client,
+        &geyser_download_url(project, platform),
+        dest,
+        checksum.as_ref(),
+        &NoProgress,
+    )
+    .await
+    .map(|_| ())
+}
+
+// Geyser-Fabric and Floodgate-Fabric both depend on Fabric API
+async fn fabric_api_file(
+    client: &Client,
+    mc_version: &str,
+) -> Result<(String, Option<Checksum>), String> {
+    let url = format!(
+        "{}/v2/project/fabric-api/version?loaders=%5B%22fabric%22%5D&game_versions=%5B%22{}%22%5D",
+        endpoint(Endpoint::Modrinth),
+        mc_version
+    );
+
+    let versions: Value = client
+        .get(url)
+        .header("User-Agent", "Cubely")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch Fabric API versions: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Fabric API versions: {}", e))?;
+
+    versions
+        .as_array()
+        .and_then(|v| v.first())
+        .and_then(|v| v["files"].as_array())
+        .and_then(|files| {
+            files
+                .iter()
+                .find(|f| f["primary"] == true)
+                .or(files.first())
+        })
+        .and_then(|f| {
+            let url = f["url"].as_str()?.to_string();
+            let checksum = f["hashes"]["sha1"]
+                .as_str()
+                .map(|h| Checksum::Sha1(h.to_string()));
+
+            Some((url, checksum))
+        })
+        .ok_or(format!("No Fabric API build found for Minecraft {}", mc_version))
+}
+
+pub async fn install_crossplay(server: &ServerConfig, mode: CrossplayMode) -> Result<(), String> {
+    let client = http_client();
+    let server_path = PathBuf::from(&server.path);
+
+    let platform = match mode {
+        CrossplayMode::Plugin => "spigot",
+        CrossplayMode::Mod => "fabric",
+        CrossplayMode::Standalone => "standalone",
+    };
+
+    download_geyser_artifact(&client, "geyser", platform, &geyser_jar_path(&server_path, mode))
+        .await?;
+
+    if let Some(floodgate) = floodgate_jar_path(&server_path, mode) {
+        download_geyser_artifact(&client, "floodgate", platform, &floodgate).await?;
+    }
+
+    if mode == CrossplayMode::Mod && !fabric_api_installed(&server_path) {
+        let (url, checksum) = fabric_api_file(&client, &server.version).await?;
+        download_file(
+            &client,
+            &url,
+            &fabric_api_jar_path(&server_path),
+            checksum.as_ref(),
+            &NoProgress,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+fn fabric_api_installed(server_path: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(server_path.join("mods")) else {
+        return false;
+    };
+
+    entries.flatten().any(|e| {
+        e.file_name()
+            .to_str()
+            .map(|n| n.starts_with("fabric-api") && n.ends_with(".jar"))
+            .unwrap_or(false)
+    })
+}
+
+fn remove_crossplay(server_path: &Path, mode: CrossplayMode) {
+    fs::remove_file(geyser_jar_path(server_path, mode)).ok();
+
+    if let Some(floodgate) = floodgate_jar_path(server_path, mode) {
+        fs::remove_file(floodgate).ok();
+    }
+
+    if mode == CrossplayMode::Standalone {
+        fs::remove_dir_all(geyser_standalone_dir(server_path)).ok();
+    }
+}
+
+/// Everything `install_crossplay` may write for `mode`
+fn crossplay_files(server_path: &Path, mode: CrossplayMode) -> Vec<PathBuf> {
+    match mode {
+        CrossplayMode::Standalone => vec![geyser_standalone_dir(server_path)],
+        _ => {
+            let mut files = vec![geyser_jar_path(server_path, mode)];
+            files.extend(floodgate_jar_path(server_path, mode));
+            if mode == CrossplayMode::Mod {
+                files.push(fabric_api_jar_path(server_path));
+            }
+            files
+        }
+    }
+}
+
+/// Geyser config.yml
+///
+/// Rewritten on every start so the remote port follows server.properties.
+pub fn write_geyser_config(server: &ServerConfig, crossplay: &CrossplayConfig) -> Result<(), String> {
+    let server_path = PathBuf::from(&server.path);
+    let config_path = geyser_config_path(&server_path, crossplay.mode);
+
+    // Without Floodgate, Bedrock players have to sign in with a Java account
+    let auth_type = match crossplay.mode {
+        CrossplayMode::Standalone => "online",
+        _ => "floodgate",
+    };
+
+    let config = format!(
+        r#"# Generated by Cubely
+bedrock:
+  address: 0.0.0.0
+  port: {bedrock_port}
+  clone-remote-port: false
+  motd1: "{name}"
+  motd2: "Cubely"
+  server-name: "{name}"
+remote:
+  address: 127.0.0.1
+  port: {java_port}
+  auth-type: {auth_type}
+floodgate-key-file: key.pem
+passthrough-motd: true
+passthrough-player-counts: true
+config-version: 4
+"#,
+        bedrock_port = crossplay.bedrock_port,
+        name = server.name.replace('"', "'"),
+        java_port = server_port(server),
+        auth_type = auth_type,
+    );
+
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    fs::write(&config_path, config).map_err(|e| format!("Failed to write Geyser config: {}", e))
+}
+
+fn save_crossplay(server: &mut ServerConfig, crossplay: Option<CrossplayConfig>) -> Result<(), String> {
+    server.crossplay = crossplay;
+
+    fs::write(
+        PathBuf::from(&server.path).join("cubely.json"),
+        serde_json::to_string_pretty(server).unwrap(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn find_stopped_server(server_id: &str, state: &AppState) -> Result<ServerConfig, String> {
+    {
+        let active = state.active_server.lock().unwrap();
+        if let Some(active) = active.as_ref() {
+            if active.server_id == server_id {
+                return Err("Stop the server before changing crossplay".into());
+            }
+        }
+    }
+
+    find_server(server_id)
+}
+
+/// Crossplay Commands
+
+#[tauri::command]
+pub async fn enable_crossplay(
+    server_id: String,
+    bedrock_port: Option<u16>,
+    state: tauri::State<'_, AppState>,
+) -> Result<CrossplayConfig, String> {
+    let mut server = find_stopped_server(&server_id, &state)?;
+    let mode = crossplay_mode(&server.loader)?;
+
+    let crossplay = CrossplayConfig {
+        enabled: true,
+        mode,
+        bedrock_port: bedrock_port.unwrap_or(DEFAULT_BEDROCK_PORT),
+    };
+
+    // A failed install only cleans up what it added, so jars the user
+    // already had in place stay put
+    let created: Vec<PathBuf> = crossplay_files(Path::new(&server.path), mode)
+        .into_iter()
+        .filter(|p| !p.exists())
+        .collect();
+
+    if let Err(e) = install_crossplay(&server, mode).await {
+        for path in created {
+            if path.is_dir() {
+                fs::remove_dir_all(path).ok();
+            } else {
+                fs::remove_file(path).ok();
+            }
+        }
+        return Err(e);
+    }
+
+    write_geyser_config(&server, &crossplay)?;
+    save_crossplay(&mut server, Some(crossplay.clone()))?;
+
+    Ok(crossplay)
+}
+
+#[tauri::command]
+pub fn disable_crossplay(
+    server_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut server = find_stopped_server(&server_id, &state)?;
+
+    if let Some(crossplay) = &server.crossplay {
+        remove_crossplay(Path::new(&server.path), crossplay.mode);
+    }
+
+    save_crossplay(&mut server, None)
+}
+
+/// Start standalone Geyser in front of the Java server
+pub fn start_geyser_standalone(java: &PathBuf, server_path: &str) -> Result<Child, String> {
+    let dir = geyser_standalone_dir(Path::new(server_path));
+
+    Command::new(java)
+        .args(["-Xmx1G", "-jar", "Geyser-Standalone.jar", "--nogui"])
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start Geyser: {}", e))
+}
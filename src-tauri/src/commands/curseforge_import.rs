@@ -0,0 +1,387 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use zip::ZipArchive;
+
+use crate::commands::creation_jobs::{CreatePhase, CreationJob};
+use crate::commands::modpack_import::{extract_overrides, safe_relative_path};
+use crate::commands::server_creation::{
+    create_server_from, CreateServerResult, LoaderType, ServerSource,
+};
+use crate::commands::server_import::default_ram_gb;
+use crate::state::app_state::AppState;
+use crate::utils::download::Checksum;
+use crate::utils::network::{endpoint, http_client, network_settings, Endpoint};
+
+/// CURSEFORGE MODPACKS
+///
+/// CurseForge packs are client zips: `manifest.json` names the game version,
+/// the loader and the project files, which are looked up through the
+/// CurseForge API (it needs a key, set in network settings) and downloaded
+/// into the server. Client-only files are left out.
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+// CurseForge class ids
+const CLASS_MODS: u64 = 6;
+const CLASS_RESOURCE_PACKS: u64 = 12;
+const CLASS_SHADERS: u64 = 6552;
+
+// CurseForge hash algorithm ids, 2 is md5
+const ALGO_SHA1: u64 = 1;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CfManifest {
+    pub minecraft: CfMinecraft,
+    pub manifest_type: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub version: Option<String>,
+
+    #[serde(default)]
+    pub author: Option<String>,
+
+    pub files: Vec<CfFileRef>,
+
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+}
+
+fn default_overrides() -> String {
+    "overrides".into()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CfMinecraft {
+    pub version: String,
+    pub mod_loaders: Vec<CfModLoader>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CfModLoader {
+    pub id: String, // "forge-47.2.0", "fabric-0.15.11", "neoforge-21.1.77"
+
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CfFileRef {
+    #[serde(rename = "projectID")]
+    pub project_id: u64,
+
+    #[serde(rename = "fileID")]
+    pub file_id: u64,
+
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// A project file resolved through the API
+#[derive(Debug, Clone)]
+pub struct CfDownload {
+    pub file_name: String,
+    pub folder: &'static str,
+    pub url: String,
+    pub sha1: Option<String>,
+}
+
+/// A parsed pack, its loader and the files a server needs
+#[derive(Debug, Clone)]
+pub struct CfPack {
+    pub archive: PathBuf,
+    pub manifest: CfManifest,
+    pub version: String,
+    pub loader: LoaderType,
+    pub loader_build: Option<String>,
+    pub downloads: Vec<CfDownload>,
+    pub client_only: Vec<String>,
+    pub unresolved: Vec<String>, // files the API returned nothing usable for
+}
+
+#[derive(Debug, Serialize)]
+pub struct CfPackSummary {
+    pub name: String,
+    pub pack_version: Option<String>,
+    pub author: Option<String>,
+    pub version: String,
+    pub loader: LoaderType,
+    pub loader_build: Option<String>,
+    pub server_files: usize,
+    pub client_only: Vec<String>,
+    pub unresolved: Vec<String>,
+}
+
+/// Maps the primary mod loader to a loader and its build
+fn resolve_loader(minecraft: &CfMinecraft) -> Result<(LoaderType, Option<String>), String> {
+    let Some(primary) = minecraft
+        .mod_loaders
+        .iter()
+        .find(|l| l.primary)
+        .or_else(|| minecraft.mod_loaders.first())
+    else {
+        return Ok((LoaderType::Vanilla, None));
+    };
+
+    let (kind, build) = primary
+        .id
+        .split_once('-')
+        .ok_or(format!("Unknown mod loader {}", primary.id))?;
+
+    let loader = match kind {
+        "forge" => LoaderType::Forge,
+        "fabric" => LoaderType::Fabric,
+        "neoforge" => LoaderType::NeoForge,
+        _ => return Err(format!("{} modpacks aren't supported", kind)),
+    };
+
+    Ok((loader, Some(build.to_string())))
+}
+
+fn read_manifest(archive: &Path) -> Result<CfManifest, String> {
+    let file = fs::File::open(archive).map_err(|e| format!("Failed to open modpack: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Not a modpack archive: {}", e))?;
+
+    let entry = zip
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| format!("{} is missing from the modpack", MANIFEST_FILE))?;
+
+    let manifest: CfManifest =
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))?;
+
+    if manifest.manifest_type != "minecraftModpack" {
+        return Err(format!("Unsupported manifest type {}", manifest.manifest_type));
+    }
+
+    Ok(manifest)
+}
+
+/// POSTs to the CurseForge API, returning the `data` array
+async fn curseforge_post(client: &Client, path: &str, body: Value) -> Result<Vec<Value>, String> {
+    let key = network_settings()
+        .curseforge_api_key
+        .ok_or("Set a CurseForge API key in network settings to import CurseForge modpacks")?;
+
+    let response = client
+        .post(format!("{}{}", endpoint(Endpoint::CurseForgeApi), path))
+        .header("x-api-key", key)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach CurseForge: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("CurseForge API returned {}", response.status()));
+    }
+
+    let json: Value = response.json().await.map_err(|e| e.to_string())?;
+
+    Ok(json["data"].as_array().cloned().unwrap_or_default())
+}
+
+/// Files with distribution disabled have no downloadUrl but still sit on the CDN
+fn cdn_url(file_id: u64, file_name: &str) -> String {
+    format!(
+        "{}/files/{}/{}/{}",
+        endpoint(Endpoint::CurseForgeCdn),
+        file_id / 1000,
+        file_id % 1000,
+        file_name
+    )
+}
+
+/// Files tagged for the client but not the server
+fn is_client_only(file: &Value) -> bool {
+    let tags: Vec<&str> = file["gameVersions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .collect();
+
+    tags.contains(&"Client") && !tags.contains(&"Server")
+}
+
+pub async fn read_curseforge_pack(archive: &Path) -> Result<CfPack, String> {
+    let manifest = read_manifest(archive)?;
+    let (loader, loader_build) = resolve_loader(&manifest.minecraft)?;
+
+    let refs: Vec<&CfFileRef> = manifest.files.iter().filter(|f| f.required).collect();
+
+    let mut downloads = Vec::new();
+    let mut client_only = Vec::new();
+    let mut unresolved = Vec::new();
+
+    if !refs.is_empty() {
+        let client = http_client();
+
+        let files = curseforge_post(
+            &client,
+            "/v1/mods/files",
+            json!({ "fileIds": refs.iter().map(|f| f.file_id).collect::<Vec<_>>() }),
+        )
+        .await?;
+
+        let projects = curseforge_post(
+            &client,
+            "/v1/mods",
+            json!({ "modIds": refs.iter().map(|f| f.project_id).collect::<Vec<_>>() }),
+        )
+        .await?;
+
+        let class_of: HashMap<u64, u64> = projects
+            .iter()
+            .filter_map(|p| Some((p["id"].as_u64()?, p["classId"].as_u64()?)))
+            .collect();
+
+        let files_by_id: HashMap<u64, &Value> = files
+            .iter()
+            .filter_map(|f| Some((f["id"].as_u64()?, f)))
+            .collect();
+
+        for file_ref in refs {
+            let Some(file) = files_by_id.get(&file_ref.file_id) else {
+                unresolved.push(format!("{}/{}", file_ref.project_id, file_ref.file_id));
+                continue;
+            };
+
+            let Some(file_name) = file["fileName"].as_str().filter(|n| safe_relative_path(n).is_some())
+            else {
+                unresolved.push(format!("{}/{}", file_ref.project_id, file_ref.file_id));
+                continue;
+            };
+
+            // Resource packs and shaders are client-side by nature
+            let folder = match class_of.get(&file_ref.project_id) {
+                Some(&CLASS_RESOURCE_PACKS) | Some(&CLASS_SHADERS) => {
+                    client_only.push(file_name.to_string());
+                    continue;
+                }
+                Some(&CLASS_MODS) | None => "mods",
+                Some(_) => {
+                    unresolved.push(file_name.to_string());
+                    continue;
+                }
+            };
+
+            if is_client_only(file) {
+                client_only.push(file_name.to_string());
+                continue;
+            }
+
+            let sha1 = file["hashes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|h| h["algo"].as_u64() == Some(ALGO_SHA1))
+                .and_then(|h| h["value"].as_str())
+                .map(String::from);
+
+            downloads.push(CfDownload {
+                file_name: file_name.to_string(),
+                folder,
+                url: file["downloadUrl"]
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| cdn_url(file_ref.file_id, file_name)),
+                sha1,
+            });
+        }
+    }
+
+    Ok(CfPack {
+        archive: archive.to_path_buf(),
+        version: manifest.minecraft.version.clone(),
+        manifest,
+        loader,
+        loader_build,
+        downloads,
+        client_only,
+        unresolved,
+    })
+}
+
+/// Downloads the pack's server files and copies its overrides.
+/// Files that couldn't be resolved or fetched are returned instead of failing the server.
+pub async fn install_curseforge_files(
+    pack: &CfPack,
+    server_path: &Path,
+    job: &CreationJob,
+) -> Result<Vec<String>, String> {
+    let client = http_client();
+    let mut failed = pack.unresolved.clone();
+
+    for file in &pack.downloads {
+        job.check_cancelled()?;
+
+        let dest = server_path.join(file.folder).join(&file.file_name);
+        let checksum = file.sha1.clone().map(Checksum::Sha1);
+
+        if let Err(e) = job.download(&client, &file.url, &dest, checksum.as_ref()).await {
+            job.check_cancelled()?;
+            job.log(format!("Failed to download {}: {}", file.file_name, e));
+            failed.push(file.file_name.clone());
+        }
+    }
+
+    job.check_cancelled()?;
+    job.set_phase(CreatePhase::WritingFiles);
+    extract_overrides(&pack.archive, &[pack.manifest.overrides.as_str()], server_path)?;
+
+    Ok(failed)
+}
+
+#[tauri::command]
+pub async fn inspect_curseforge_pack(path: String) -> Result<CfPackSummary, String> {
+    let pack = read_curseforge_pack(Path::new(&path)).await?;
+
+    Ok(CfPackSummary {
+        name: pack.manifest.name.clone(),
+        pack_version: pack.manifest.version.clone(),
+        author: pack.manifest.author.clone(),
+        version: pack.version,
+        loader: pack.loader,
+        loader_build: pack.loader_build,
+        server_files: pack.downloads.len(),
+        client_only: pack.client_only,
+        unresolved: pack.unresolved,
+    })
+}
+
+#[tauri::command]
+pub async fn import_curseforge_pack(
+    path: String,
+    name: Option<String>,
+    ram_gb: Option<u8>,
+    job_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CreateServerResult, String> {
+    let pack = read_curseforge_pack(Path::new(&path)).await?;
+
+    let name = name.unwrap_or_else(|| pack.manifest.name.clone());
+    let ram_gb = ram_gb.unwrap_or_else(|| default_ram_gb(&pack.loader));
+
+    create_server_from(
+        name,
+        pack.version.clone(),
+        pack.loader.clone(),
+        ram_gb,
+        ServerSource::CurseForge(pack),
+        job_id,
+        &state,
+    )
+    .await
+}
@@ -1,18 +1,37 @@
 use std::{path::{Path, PathBuf}, process::Command};
 use tauri::{AppHandle, Manager};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use std::{fs, io::Write};
 use zip::ZipArchive;
 
+use crate::commands::artifact_cache::cached_download;
+use crate::commands::manifest_cache::fetch_json_cached;
+use crate::utils::network::{endpoint, http_client, Endpoint};
+use crate::utils::download::{Checksum, DownloadObserver};
+
 /// Installing Java
 
-#[derive(Debug, Clone, Copy)]
-pub enum JavaVersion {
-    Java8,
-    Java17,
-    Java21,
+/// A Java major version (8, 17, 21, ...), installed under `<java base>/<major>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JavaVersion(pub u32);
+
+impl JavaVersion {
+    pub fn major(self) -> u32 {
+        self.0
+    }
+}
+
+/// Java required by a server: the `javaVersion.majorVersion` cached from Mojang's
+/// version JSON at creation, or a guess from the version id for older configs.
+pub fn server_java(java_major: Option<u32>, mc_version: &str) -> JavaVersion {
+    java_major
+        .map(JavaVersion)
+        .unwrap_or_else(|| require_java(mc_version))
 }
 
+// Fallback only, Mojang's metadata is the source of truth
 pub fn require_java(mc_version: &str) -> JavaVersion {
     let parts: Vec<u32> = mc_version
         .split('.')
@@ -20,17 +39,20 @@ pub fn require_java(mc_version: &str) -> JavaVersion {
         .collect();
 
     if parts.len() < 2 {
-        return JavaVersion::Java17
+        return JavaVersion(21)
     }
 
     let major = parts[0];
     let minor = parts[1];
+    let patch = parts.get(2).copied().unwrap_or(0);
 
     match (major, minor) {
-        (1, 0..=16) => JavaVersion::Java8, // 1.0 - 1.16.x
-        (1, 17..=20) => JavaVersion::Java17, // 1.17 - 1.20.x
-        (1, 21..) => JavaVersion::Java21, // 1.21+
-        _ => JavaVersion::Java17, // Fallback
+        (1, 0..=16) => JavaVersion(8), // 1.0 - 1.16.x
+        (1, 17..=19) => JavaVersion(17), // 1.17 - 1.19.x
+        (1, 20) if patch < 5 => JavaVersion(17), // 1.20 - 1.20.4
+        (1, 20..) => JavaVersion(21), // 1.20.5+
+        (26.., _) => JavaVersion(25), // year based versions (26.1+)
+        _ => JavaVersion(21), // Fallback
     }
 }
 
@@ -44,65 +66,66 @@ pub fn java_binary(base: &PathBuf, version: JavaVersion) -> PathBuf {
     #[cfg(all(unix, not(target_os = "macos")))]
     let bin = "bin/java";
 
-    match version {
-        JavaVersion::Java8 => base.join("8").join(bin),
-        JavaVersion::Java17 => base.join("17").join(bin),
-        JavaVersion::Java21 => base.join("21").join(bin),
-    }
+    java_version_dir(base, version).join(bin)
 }
 
-fn java_download_url(version: JavaVersion) -> &'static str {
-    match version {
-        JavaVersion::Java8 => {
-            #[cfg(target_os = "windows")]
-            { "https://api.adoptium.net/v3/binary/latest/8/ga/windows/x64/jre/hotspot/normal/eclipse" }
-
-            #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-            { "https://api.adoptium.net/v3/binary/latest/8/ga/mac/x64/jre/hotspot/normal/eclipse" }
-
-            #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-            { "https://api.adoptium.net/v3/binary/latest/8/ga/mac/aarch64/jre/hotspot/normal/eclipse" }
-
-            #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-            { "https://api.adoptium.net/v3/binary/latest/8/ga/linux/x64/jre/hotspot/normal/eclipse" }
-        }
-
-        JavaVersion::Java17 => {
-            #[cfg(target_os = "windows")]
-            { "https://api.adoptium.net/v3/binary/latest/17/ga/windows/x64/jre/hotspot/normal/eclipse" }
-
-            #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-            { "https://api.adoptium.net/v3/binary/latest/17/ga/mac/x64/jre/hotspot/normal/eclipse" }
+fn java_version_dir(base: &PathBuf, version: JavaVersion) -> PathBuf {
+    base.join(version.major().to_string())
+}
 
-            #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-            { "https://api.adoptium.net/v3/binary/latest/17/ga/mac/aarch64/jre/hotspot/normal/eclipse" }
+fn adoptium_platform() -> (&'static str, &'static str) {
+    #[cfg(target_os = "windows")]
+    let platform = ("windows", "x64");
 
-            #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-            { "https://api.adoptium.net/v3/binary/latest/17/ga/linux/x64/jre/hotspot/normal/eclipse" }
-        }
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    let platform = ("mac", "x64");
 
-        JavaVersion::Java21 => {
-            #[cfg(target_os = "windows")]
-            { "https://api.adoptium.net/v3/binary/latest/21/ga/windows/x64/jre/hotspot/normal/eclipse" }
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    let platform = ("mac", "aarch64");
 
-            #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-            { "https://api.adoptium.net/v3/binary/latest/21/ga/mac/x64/jre/hotspot/normal/eclipse" }
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    let platform = ("linux", "x64");
 
-            #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-            { "https://api.adoptium.net/v3/binary/latest/21/ga/mac/aarch64/jre/hotspot/normal/eclipse" }
+    platform
+}
 
-            #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-            { "https://api.adoptium.net/v3/binary/latest/21/ga/linux/x64/jre/hotspot/normal/eclipse" }
-        }
-    }
+/// Download link and SHA-256 of the latest Temurin JRE for `version`
+async fn java_package(client: &Client, version: JavaVersion) -> Result<(String, Option<Checksum>), String> {
+    let (os, arch) = adoptium_platform();
+
+    let assets = fetch_json_cached(
+        client,
+        &format!(
+            "{}/v3/assets/latest/{}/hotspot?architecture={}&image_type=jre&os={}&vendor=eclipse",
+            endpoint(Endpoint::Adoptium),
+            version.major(),
+            arch,
+            os
+        ),
+    )
+    .await?
+    .data;
+
+    let package = assets
+        .as_array()
+        .and_then(|a| a.first())
+        .map(|a| &a["binary"]["package"])
+        .ok_or(format!("No Java {} runtime available for this platform", version.major()))?;
+
+    let link = package["link"]
+        .as_str()
+        .ok_or("Invalid Adoptium package metadata")?
+        .to_string();
+
+    let checksum = package["checksum"]
+        .as_str()
+        .map(|h| Checksum::Sha256(h.to_string()));
+
+    Ok((link, checksum))
 }
 
 fn installing_marker(base: &PathBuf, version: JavaVersion) -> PathBuf {
-    match version {
-        JavaVersion::Java8 => base.join("8/.installing"),
-        JavaVersion::Java17 => base.join("17/.installing"),
-        JavaVersion::Java21 => base.join("21/.installing"),
-    }
+    java_version_dir(base, version).join(".installing")
 }
 
 fn java_archive_path(base: &PathBuf) -> PathBuf {
@@ -113,11 +136,16 @@ fn java_archive_path(base: &PathBuf) -> PathBuf {
     { base.join("java.tar.gz") }
 }
 
-pub async fn install_java(base: &PathBuf, version: JavaVersion) -> Result<(), String> {
+pub async fn install_java(
+    base: &PathBuf,
+    version: JavaVersion,
+    observer: &dyn DownloadObserver,
+) -> Result<(), String> {
     #[cfg(all(target_os = "linux", not(target_arch = "x86_64")))]
     compile_error!("Unsupported Linux architecture for Java installer");
 
-    let url = java_download_url(version);
+    let client = http_client();
+    let (url, checksum) = java_package(&client, version).await?;
     let target_dir = base; // C:\Users\<YOUR_USERNAME>\AppData\Roaming\com.tauri.dev\java
 
     fs::create_dir_all(&target_dir).map_err(|e| e.to_string())?;
@@ -125,23 +153,10 @@ pub async fn install_java(base: &PathBuf, version: JavaVersion) -> Result<(), St
     let marker = installing_marker(base, version);
     fs::write(&marker, b"").ok();
 
-    let bytes = Client::new()
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .bytes()
-        .await
-        .map_err(|e| e.to_string())?;
-
     let archive_path = java_archive_path(target_dir);
-    fs::write(&archive_path, &bytes).map_err(|e| e.to_string())?;
+    cached_download(&client, &url, &archive_path, checksum.as_ref(), observer).await?;
 
-    let version_dir = match version {
-        JavaVersion::Java8 => target_dir.join("8"),
-        JavaVersion::Java17 => target_dir.join("17"),
-        JavaVersion::Java21 => target_dir.join("21"),
-    };
+    let version_dir = java_version_dir(target_dir, version);
 
     fs::create_dir_all(&version_dir).map_err(|e| e.to_string())?;
 
@@ -227,6 +242,25 @@ pub async fn install_java(base: &PathBuf, version: JavaVersion) -> Result<(), St
     Ok(())
 }
 
+/// Installs `version` into `base` if it's missing and returns its java binary
+pub async fn ensure_java(
+    base: &PathBuf,
+    version: JavaVersion,
+    observer: &dyn DownloadObserver,
+) -> Result<PathBuf, String> {
+    if !java_installed(base, version) {
+        install_java(base, version, observer).await?;
+    }
+
+    let java = java_binary(base, version);
+
+    if !java.exists() {
+        return Err(format!("Java not found at {}", java.display()));
+    }
+
+    Ok(java)
+}
+
 /// Java Verification and Cleanup
 
 fn java_structure_ok(java_home: &Path) -> bool {
@@ -256,11 +290,7 @@ fn java_runs(java_bin: &Path) -> bool {
 }
 
 fn cleanup_java(base: &PathBuf, version: JavaVersion) {
-    let dir = match version {
-        JavaVersion::Java8 => base.join("8"),
-        JavaVersion::Java17 => base.join("17"),
-        JavaVersion::Java21 => base.join("21"),
-    };
+    let dir = java_version_dir(base, version);
 
     let archive = java_archive_path(base);
 
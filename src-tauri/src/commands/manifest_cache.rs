@@ -0,0 +1,173 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::state::app_state::AppState;
+use crate::utils::network::mirrored;
+use crate::utils::path::cache_dir;
+
+/// Offline manifests
+///
+/// Every version manifest, loader list and build lookup that succeeds is
+/// written under `cache_dir()/manifests`, keyed by a hash of its URL. When the
+/// network is unreachable the last copy is served instead and marked offline,
+/// so version lists keep working and servers can be created from the artifact
+/// cache without a connection.
+
+// Manifests older than this are reported as stale
+const STALE_AFTER_SECS: i64 = 24 * 60 * 60;
+
+// Keeps an unreachable network from stalling the fallback
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone)]
+pub struct Fetched<T> {
+    pub data: T,
+    pub fetched_at: i64, // unix seconds
+    pub offline: bool,   // served from disk because the request failed
+}
+
+impl<T> Fetched<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Fetched<U> {
+        Fetched {
+            data: f(self.data),
+            fetched_at: self.fetched_at,
+            offline: self.offline,
+        }
+    }
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn manifest_path(url: &str) -> PathBuf {
+    let hash: String = Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    cache_dir().join("manifests").join(hash)
+}
+
+/// Last stored response for `url`, timestamped with when it was fetched
+pub fn cached_text(url: &str) -> Option<Fetched<String>> {
+    let path = manifest_path(url);
+    let body = fs::read_to_string(&path).ok()?;
+
+    let fetched_at = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    Some(Fetched {
+        data: body,
+        fetched_at,
+        offline: true,
+    })
+}
+
+pub fn cached_json(url: &str) -> Option<Fetched<Value>> {
+    let cached = cached_text(url)?;
+    let data = serde_json::from_str(&cached.data).ok()?;
+
+    Some(cached.map(|_| data))
+}
+
+async fn fetch_live(client: &Client, url: &str) -> Result<String, String> {
+    client
+        .get(mirrored(url))
+        .header("User-Agent", "Cubely")
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// GETs `url`, storing the body on success and falling back to the stored copy on failure
+pub async fn fetch_cached(client: &Client, url: &str) -> Result<Fetched<String>, String> {
+    match fetch_live(client, url).await {
+        Ok(body) => {
+            let path = manifest_path(url);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).ok();
+            }
+            fs::write(&path, &body).ok();
+
+            Ok(Fetched {
+                data: body,
+                fetched_at: now(),
+                offline: false,
+            })
+        }
+        Err(e) => cached_text(url).ok_or(format!("{} (offline, nothing cached)", e)),
+    }
+}
+
+pub async fn fetch_json_cached(client: &Client, url: &str) -> Result<Fetched<Value>, String> {
+    let fetched = fetch_cached(client, url).await?;
+    let data: Value = serde_json::from_str(&fetched.data)
+        .map_err(|e| format!("Invalid response from {}: {}", url, e))?;
+
+    Ok(fetched.map(|_| data))
+}
+
+/// Manifest Status
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Freshness {
+    pub fetched_at: i64,
+    pub offline: bool,
+}
+
+impl<T> From<&Fetched<T>> for Freshness {
+    fn from(fetched: &Fetched<T>) -> Self {
+        Self {
+            fetched_at: fetched.fetched_at,
+            offline: fetched.offline,
+        }
+    }
+}
+
+/// Where the version list and loader support currently come from
+#[derive(Debug, Clone, Default)]
+pub struct ManifestStatus {
+    pub versions: Option<Freshness>,
+    pub loaders: Option<Freshness>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestStatusInfo {
+    pub offline: bool,
+    pub stale: bool,
+    pub versions_fetched_at: Option<i64>,
+    pub loaders_fetched_at: Option<i64>,
+}
+
+#[tauri::command]
+pub fn get_manifest_status(state: tauri::State<'_, AppState>) -> ManifestStatusInfo {
+    let status = state.manifest_status.lock().unwrap();
+    let known = [status.versions, status.loaders];
+    let known = known.iter().flatten();
+
+    ManifestStatusInfo {
+        offline: known.clone().any(|f| f.offline),
+        stale: known.clone().any(|f| now() - f.fetched_at > STALE_AFTER_SECS),
+        versions_fetched_at: status.versions.map(|f| f.fetched_at),
+        loaders_fetched_at: status.loaders.map(|f| f.fetched_at),
+    }
+}
@@ -7,3 +7,22 @@ pub mod java_manager;
 pub mod ngrok_manager;
 pub mod playit_manager;
 pub mod discord_rpc;
+pub mod crossplay_manager;
+pub mod creation_jobs;
+pub mod artifact_cache;
+pub mod manifest_cache;
+pub mod network_settings;
+pub mod server_upgrade;
+pub mod server_import;
+pub mod server_templates;
+pub mod modpack_import;
+pub mod curseforge_import;
+pub mod modpack_export;
+pub mod mod_inventory;
+pub mod mod_checker;
+pub mod mod_sources;
+pub mod modrinth_browser;
+pub mod mod_updates;
+pub mod mod_profiles;
+pub mod mod_bisect;
+pub mod plugin_manager;
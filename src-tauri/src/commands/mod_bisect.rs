@@ -0,0 +1,242 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::mod_inventory::{scan_mods_dir, DependencyKind};
+use crate::commands::mod_profiles::{apply_enabled_set, content_dir, enabled_jars};
+use crate::commands::server_management::{ensure_stopped, find_server, ServerConfig};
+use crate::state::app_state::AppState;
+
+/// FINDING A CRASHING MOD
+///
+/// Bisects the enabled jars across launches: each launch runs with half of
+/// the remaining suspects (plus the jars they require, so a missing library
+/// doesn't pass for the crash), and the outcome halves the suspects again
+/// until one is left. Launches are judged when the server exits, see
+/// `record_bisect_launch`, and the original set is enabled again at the end.
+/// The state lives in the server's `cubely-mod-bisect.json`.
+
+pub const BISECT_FILE: &str = "cubely-mod-bisect.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModBisect {
+    pub original: Vec<String>, // enabled before the bisect
+    pub suspects: Vec<String>,
+    pub testing: Vec<String>, // enabled for the next launch
+    pub requires: HashMap<String, Vec<String>>, // jar -> jars it needs
+    pub step: u32,
+    pub finished: bool,
+    pub culprit: Option<String>,
+    pub started_at: i64,
+}
+
+impl ModBisect {
+    fn load(server_path: &Path) -> Option<Self> {
+        fs::read_to_string(server_path.join(BISECT_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+    }
+
+    fn save(&self, server_path: &Path) -> Result<(), String> {
+        fs::write(
+            server_path.join(BISECT_FILE),
+            serde_json::to_string_pretty(self).unwrap(),
+        )
+        .map_err(|e| format!("Failed to save mod bisect: {}", e))
+    }
+
+    /// `jars` and everything they require
+    fn with_requirements(&self, jars: &[String]) -> Vec<String> {
+        let mut seen: HashSet<String> = jars.iter().cloned().collect();
+        let mut queue: VecDeque<String> = jars.iter().cloned().collect();
+
+        while let Some(jar) = queue.pop_front() {
+            for needed in self.requires.get(&jar).into_iter().flatten() {
+                if seen.insert(needed.clone()) {
+                    queue.push_back(needed.clone());
+                }
+            }
+        }
+
+        self.original
+            .iter()
+            .filter(|j| seen.contains(*j))
+            .cloned()
+            .collect()
+    }
+
+    fn plan_next(&mut self) {
+        let half = self.suspects[..self.suspects.len() / 2].to_vec();
+        let testing = self.with_requirements(&half);
+
+        // When the requirements drag in every suspect nothing would be learned
+        self.testing = match self.suspects.iter().all(|s| testing.contains(s)) {
+            true => half,
+            false => testing,
+        };
+    }
+
+    /// Narrows the suspects down by the outcome of the launch with `testing`
+    fn advance(&mut self, crashed: bool) {
+        self.suspects
+            .retain(|s| self.testing.contains(s) == crashed);
+        self.step += 1;
+
+        match self.suspects.len() {
+            // Nothing left means the crash didn't reproduce consistently
+            0 | 1 => {
+                self.finished = true;
+                self.culprit = self.suspects.first().cloned();
+                self.testing = self.original.clone();
+            }
+            _ => self.plan_next(),
+        }
+    }
+}
+
+/// Which enabled jar needs which, from the mod metadata
+fn jar_requirements(dir: &Path) -> HashMap<String, Vec<String>> {
+    let mods = scan_mods_dir(dir, false);
+
+    let mut providers: HashMap<&str, &str> = HashMap::new();
+    for info in &mods {
+        let ids = info.id.iter().chain(info.provides.iter().map(|p| &p.id));
+
+        for id in ids {
+            providers
+                .entry(id.as_str())
+                .or_insert(info.file_name.as_str());
+        }
+    }
+
+    mods.iter()
+        .map(|info| {
+            let needed: Vec<String> = info
+                .dependencies
+                .iter()
+                .filter(|d| d.kind == DependencyKind::Required)
+                .filter_map(|d| providers.get(d.id.as_str()))
+                .filter(|jar| **jar != info.file_name)
+                .map(|jar| jar.to_string())
+                .collect();
+
+            (info.file_name.clone(), needed)
+        })
+        .collect()
+}
+
+/// Applies the outcome of a launch to a running bisect and enables the jars
+/// for the next one, restoring the original set once it is over
+fn record_outcome(server: &ServerConfig, crashed: bool) -> Result<ModBisect, String> {
+    let server_path = PathBuf::from(&server.path);
+    let mut bisect = ModBisect::load(&server_path).ok_or("No mod bisect in progress")?;
+
+    bisect.advance(crashed);
+    apply_enabled_set(&content_dir(server)?, &bisect.testing)?;
+
+    match bisect.finished {
+        true => fs::remove_file(server_path.join(BISECT_FILE)).map_err(|e| e.to_string())?,
+        false => bisect.save(&server_path)?,
+    }
+
+    Ok(bisect)
+}
+
+/// Records a launch that just exited, None when no bisect is running
+pub fn record_bisect_launch(
+    server: &ServerConfig,
+    crashed: bool,
+) -> Option<Result<ModBisect, String>> {
+    if !Path::new(&server.path).join(BISECT_FILE).exists() {
+        return None;
+    }
+
+    Some(record_outcome(server, crashed))
+}
+
+/// Commands
+
+#[tauri::command]
+pub fn start_mod_bisect(
+    server_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModBisect, String> {
+    ensure_stopped(&server_id, &state, "Stop the server before bisecting mods")?;
+
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+    let dir = content_dir(&server)?;
+
+    if ModBisect::load(&server_path).is_some() {
+        return Err("A mod bisect is already in progress".into());
+    }
+
+    let original = enabled_jars(&dir);
+    if original.len() < 2 {
+        return Err("Bisecting needs at least two enabled mods".into());
+    }
+
+    let mut bisect = ModBisect {
+        suspects: original.clone(),
+        testing: Vec::new(),
+        requires: jar_requirements(&dir),
+        original,
+        step: 0,
+        finished: false,
+        culprit: None,
+        started_at: Utc::now().timestamp(),
+    };
+
+    bisect.plan_next();
+    apply_enabled_set(&dir, &bisect.testing)?;
+    bisect.save(&server_path)?;
+
+    Ok(bisect)
+}
+
+#[tauri::command]
+pub fn get_mod_bisect(server_id: String) -> Result<Option<ModBisect>, String> {
+    let server = find_server(&server_id)?;
+    Ok(ModBisect::load(Path::new(&server.path)))
+}
+
+/// Records a launch by hand, for one that didn't go through Cubely
+#[tauri::command]
+pub fn record_bisect_result(
+    server_id: String,
+    crashed: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModBisect, String> {
+    ensure_stopped(
+        &server_id,
+        &state,
+        "Stop the server before recording the result",
+    )?;
+
+    let server = find_server(&server_id)?;
+    record_outcome(&server, crashed)
+}
+
+#[tauri::command]
+pub fn cancel_mod_bisect(
+    server_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    ensure_stopped(
+        &server_id,
+        &state,
+        "Stop the server before cancelling the bisect",
+    )?;
+
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+    let bisect = ModBisect::load(&server_path).ok_or("No mod bisect in progress")?;
+
+    apply_enabled_set(&content_dir(&server)?, &bisect.original)?;
+    fs::remove_file(server_path.join(BISECT_FILE)).map_err(|e| e.to_string())
+}
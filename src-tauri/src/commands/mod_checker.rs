@@ -0,0 +1,444 @@
+use std::{cmp::Ordering, collections::HashMap, path::PathBuf};
+
+use serde::Serialize;
+
+use crate::commands::mod_inventory::{
+    scan_mods_dir, DependencyKind, ModDependency, ModEnvironment, ModInfo, ModPlatform,
+};
+use crate::commands::plugin_manager::check_plugins;
+use crate::commands::server_creation::LoaderType;
+use crate::commands::server_management::{find_server, ServerConfig};
+
+/// MOD SET CHECKS
+///
+/// Catches what makes a loader refuse to start, before it gets the chance:
+/// missing or mismatched dependencies, duplicate mod ids, declared
+/// incompatibilities, mods for another loader or Minecraft version, and
+/// client-only mods. Version ranges use each loader's own syntax, Fabric's
+/// semver predicates (">=0.15", "~1.20", "1.20.x") and Forge's maven
+/// ranges ("[47,)"). Versions that can't be compared are let through.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    MissingDependency,
+    DependencyVersion,
+    DuplicateMod,
+    Incompatible,
+    WrongLoader,
+    WrongMinecraftVersion,
+    ClientOnly,
+    ApiVersion,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModIssue {
+    pub severity: IssueSeverity,
+    pub kind: IssueKind,
+    pub file_name: String,
+    pub mod_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModCheckReport {
+    pub issues: Vec<ModIssue>,
+    pub has_errors: bool,
+}
+
+impl ModCheckReport {
+    fn push(&mut self, severity: IssueSeverity, kind: IssueKind, info: &ModInfo, message: String) {
+        self.add(ModIssue {
+            severity,
+            kind,
+            file_name: info.file_name.clone(),
+            mod_id: info.id.clone(),
+            message,
+        });
+    }
+
+    pub fn add(&mut self, issue: ModIssue) {
+        self.has_errors |= issue.severity == IssueSeverity::Error;
+        self.issues.push(issue);
+    }
+
+    /// The first few errors, for refusing a launch
+    pub fn summary(&self) -> String {
+        let errors: Vec<&str> = self
+            .issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .map(|i| i.message.as_str())
+            .collect();
+
+        match errors.len() {
+            0..=3 => errors.join("; "),
+            n => format!("{} and {} more", errors[..3].join("; "), n - 3),
+        }
+    }
+}
+
+/// Versions
+
+struct Version {
+    parts: Vec<u64>,
+    pre_release: bool,
+}
+
+fn parse_version(version: &str) -> Option<Version> {
+    // Build metadata never affects ordering
+    let version = version.trim().split('+').next()?;
+
+    let (core, pre_release) = match version.split_once('-') {
+        Some((core, _)) => (core, true),
+        None => (version, false),
+    };
+
+    let parts = core
+        .split('.')
+        .map(|p| p.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+
+    Some(Version { parts, pre_release })
+}
+
+/// Number by number, missing numbers count as 0 and a pre-release sorts before its release
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a, b) = (parse_version(a)?, parse_version(b)?);
+    let len = a.parts.len().max(b.parts.len());
+
+    for i in 0..len {
+        let order = a.parts.get(i).unwrap_or(&0).cmp(b.parts.get(i).unwrap_or(&0));
+
+        if order != Ordering::Equal {
+            return Some(order);
+        }
+    }
+
+    Some(b.pre_release.cmp(&a.pre_release))
+}
+
+/// One Fabric term: "*", ">=1.2", "~1.20", "^1.2.3", "1.20.x" or an exact version
+fn matches_fabric_term(version: &str, term: &str) -> Option<bool> {
+    if term == "*" {
+        return Some(true);
+    }
+
+    for (op, accept) in [
+        (">=", &[Ordering::Greater, Ordering::Equal][..]),
+        ("<=", &[Ordering::Less, Ordering::Equal][..]),
+        (">", &[Ordering::Greater][..]),
+        ("<", &[Ordering::Less][..]),
+        ("=", &[Ordering::Equal][..]),
+    ] {
+        if let Some(bound) = term.strip_prefix(op) {
+            return compare_versions(version, bound).map(|o| accept.contains(&o));
+        }
+    }
+
+    // ~1.20.1 allows 1.20.x from 1.20.1, ^1.2.3 allows 1.x.x from 1.2.3
+    for (op, bumped) in [("~", 1), ("^", 0)] {
+        if let Some(bound) = term.strip_prefix(op) {
+            let base = parse_version(bound)?;
+            let index = bumped.min(base.parts.len() - 1);
+
+            let mut next = base.parts[..=index].to_vec();
+            next[index] += 1;
+            let next = next.iter().map(u64::to_string).collect::<Vec<_>>().join(".");
+
+            let above = compare_versions(version, bound)? != Ordering::Less;
+            let below = compare_versions(version, &next)? == Ordering::Less;
+
+            return Some(above && below);
+        }
+    }
+
+    // 1.20.x matches anything starting with 1.20
+    if let Some(prefix) = term
+        .strip_suffix(".x")
+        .or_else(|| term.strip_suffix(".X"))
+        .or_else(|| term.strip_suffix(".*"))
+    {
+        let (version, prefix) = (parse_version(version)?, parse_version(prefix)?);
+        return Some(version.parts.starts_with(&prefix.parts));
+    }
+
+    compare_versions(version, term).map(|o| o == Ordering::Equal)
+}
+
+/// Alternatives joined by "||", each a space separated list of terms that must all hold
+fn matches_fabric(version: &str, predicate: &str) -> Option<bool> {
+    let mut any = false;
+
+    for alternative in predicate.split("||") {
+        let mut all = true;
+
+        for term in alternative.split_whitespace() {
+            all &= matches_fabric_term(version, term)?;
+        }
+
+        any |= all;
+    }
+
+    Some(any)
+}
+
+/// Maven ranges: "[1.0,2.0)", "[47,)", "[1.20.1]" or several joined by commas.
+/// A bare version is only a recommendation.
+fn matches_maven(version: &str, range: &str) -> Option<bool> {
+    let mut rest = range.trim();
+
+    if !rest.starts_with(['[', '(']) {
+        return Some(true);
+    }
+
+    let mut any = false;
+
+    while !rest.is_empty() {
+        let end = rest.find([']', ')'])?;
+        let (segment, tail) = rest.split_at(end + 1);
+        rest = tail.trim_start_matches(',').trim();
+
+        if segment.len() < 2 || !segment.starts_with(['[', '(']) {
+            return None;
+        }
+
+        let inclusive_low = segment.starts_with('[');
+        let inclusive_high = segment.ends_with(']');
+        let inner = &segment[1..segment.len() - 1];
+
+        let Some((low, high)) = inner.split_once(',') else {
+            any |= compare_versions(version, inner.trim())? == Ordering::Equal;
+            continue;
+        };
+
+        let above = match low.trim() {
+            "" => true,
+            low => match compare_versions(version, low)? {
+                Ordering::Greater => true,
+                Ordering::Equal => inclusive_low,
+                Ordering::Less => false,
+            },
+        };
+
+        let below = match high.trim() {
+            "" => true,
+            high => match compare_versions(version, high)? {
+                Ordering::Less => true,
+                Ordering::Equal => inclusive_high,
+                Ordering::Greater => false,
+            },
+        };
+
+        any |= above && below;
+    }
+
+    Some(any)
+}
+
+/// Whether `version` satisfies a dependency, None when it can't be told
+fn satisfies(platform: ModPlatform, version: &str, range: &str) -> Option<bool> {
+    match platform {
+        ModPlatform::Fabric | ModPlatform::Quilt => matches_fabric(version, range),
+        ModPlatform::Forge | ModPlatform::NeoForge => matches_maven(version, range),
+    }
+}
+
+/// The set
+
+fn server_platform(loader: &LoaderType) -> Option<ModPlatform> {
+    match loader {
+        LoaderType::Fabric => Some(ModPlatform::Fabric),
+        LoaderType::Forge => Some(ModPlatform::Forge),
+        LoaderType::NeoForge => Some(ModPlatform::NeoForge),
+        _ => None,
+    }
+}
+
+/// Ids the server itself provides
+fn builtin_mods(server: &ServerConfig) -> HashMap<String, Option<String>> {
+    let mut builtins = HashMap::from([("minecraft".to_string(), Some(server.version.clone()))]);
+
+    let loader_id = match server.loader {
+        LoaderType::Fabric => "fabricloader",
+        LoaderType::Forge => "forge",
+        LoaderType::NeoForge => "neoforge",
+        _ => return builtins,
+    };
+
+    builtins.insert(loader_id.to_string(), server.loader_build.clone());
+    builtins
+}
+
+fn check_dependency(
+    report: &mut ModCheckReport,
+    info: &ModInfo,
+    platform: ModPlatform,
+    dependency: &ModDependency,
+    available: &HashMap<String, Option<String>>,
+) {
+    // The Java version is handled by the managed runtime
+    if dependency.id == "java" {
+        return;
+    }
+
+    let installed = available.get(&dependency.id);
+
+    // Only when both versions are known and don't match
+    let mismatch = match (installed, &dependency.versions) {
+        (Some(Some(version)), Some(range)) => satisfies(platform, version, range) == Some(false),
+        _ => false,
+    };
+
+    let range = dependency.versions.as_deref().unwrap_or("any version");
+
+    match dependency.kind {
+        DependencyKind::Required if installed.is_none() => report.push(
+            IssueSeverity::Error,
+            IssueKind::MissingDependency,
+            info,
+            format!("{} needs {} ({}), which isn't installed", info.name, dependency.id, range),
+        ),
+        DependencyKind::Required | DependencyKind::Optional if mismatch => {
+            let installed = installed.cloned().flatten().unwrap_or_default();
+
+            let kind = if dependency.id == "minecraft" {
+                IssueKind::WrongMinecraftVersion
+            } else {
+                IssueKind::DependencyVersion
+            };
+
+            // Forge refuses mismatched optional dependencies too, Fabric only warns
+            let severity = match (dependency.kind, platform) {
+                (DependencyKind::Optional, ModPlatform::Fabric | ModPlatform::Quilt) => {
+                    IssueSeverity::Warning
+                }
+                _ => IssueSeverity::Error,
+            };
+
+            report.push(
+                severity,
+                kind,
+                info,
+                format!(
+                    "{} needs {} {}, but {} is installed",
+                    info.name, dependency.id, range, installed
+                ),
+            );
+        }
+        DependencyKind::Incompatible if installed.is_some() => {
+            let conflicts = match (installed, &dependency.versions) {
+                (Some(Some(version)), Some(range)) => {
+                    satisfies(platform, version, range).unwrap_or(true)
+                }
+                _ => true,
+            };
+
+            if conflicts {
+                report.push(
+                    IssueSeverity::Error,
+                    IssueKind::Incompatible,
+                    info,
+                    format!("{} doesn't work together with {}", info.name, dependency.id),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Checks the jars in a server's `mods/` (`plugins/` on Paper), empty for servers that load neither
+pub fn check_mods(server: &ServerConfig) -> ModCheckReport {
+    if server.loader == LoaderType::Paper {
+        return check_plugins(server);
+    }
+
+    let mut report = ModCheckReport::default();
+
+    let Some(platform) = server_platform(&server.loader) else {
+        return report;
+    };
+
+    let mods = scan_mods_dir(&PathBuf::from(&server.path).join("mods"), false);
+
+    let mut available = builtin_mods(server);
+    let mut owners: HashMap<&str, Vec<&ModInfo>> = HashMap::new();
+
+    for info in &mods {
+        if let Some(id) = &info.id {
+            owners.entry(id).or_default().push(info);
+            available.insert(id.clone(), info.version.clone());
+        }
+
+        for provided in &info.provides {
+            available
+                .entry(provided.id.clone())
+                .or_insert(provided.version.clone());
+        }
+    }
+
+    for (id, jars) in &owners {
+        if jars.len() > 1 {
+            let files: Vec<&str> = jars.iter().map(|j| j.file_name.as_str()).collect();
+
+            report.push(
+                IssueSeverity::Error,
+                IssueKind::DuplicateMod,
+                jars[1],
+                format!("{} is installed more than once: {}", id, files.join(", ")),
+            );
+        }
+    }
+
+    for info in &mods {
+        // Plain libraries carry no metadata
+        let Some(mod_platform) = info.platform else {
+            continue;
+        };
+
+        if mod_platform != platform {
+            report.push(
+                IssueSeverity::Error,
+                IssueKind::WrongLoader,
+                info,
+                format!(
+                    "{} is a {:?} mod, this server runs {:?}",
+                    info.name, mod_platform, server.loader
+                ),
+            );
+            continue;
+        }
+
+        if info.environment == ModEnvironment::Client {
+            report.push(
+                IssueSeverity::Warning,
+                IssueKind::ClientOnly,
+                info,
+                format!("{} only works on clients and can be removed", info.name),
+            );
+        }
+
+        for dependency in &info.dependencies {
+            check_dependency(&mut report, info, platform, dependency, &available);
+        }
+    }
+
+    report
+}
+
+#[tauri::command]
+pub async fn check_server_mods(server_id: String) -> Result<ModCheckReport, String> {
+    let server = find_server(&server_id)?;
+
+    // Sync commands run on the main thread, a big pack would freeze the window
+    tauri::async_runtime::spawn_blocking(move || check_mods(&server))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,624 @@
+use std::{
+    fs,
+    io::{Cursor, Read, Seek},
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::commands::server_management::find_server;
+
+/// MOD INVENTORY
+///
+/// Reads what each jar in a server's `mods/` says about itself. Fabric and
+/// Quilt describe mods in JSON, Forge and NeoForge in `mods.toml`, and
+/// pre-1.13 Forge mods in `mcmod.info`. Jars without any of these are listed
+/// by file name only. Mods bundled inside a jar (jar-in-jar) count as provided by it.
+
+// Icons bigger than this are left out of the listing
+const MAX_ICON_BYTES: usize = 256 * 1024;
+
+// Where Fabric/Quilt and Forge/NeoForge keep bundled jars
+const NESTED_JAR_DIRS: &[&str] = &["META-INF/jars/", "META-INF/jarjar/"];
+
+// Bundled jars can bundle jars too, but not endlessly
+const MAX_NESTING: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModEnvironment {
+    Client,
+    Server,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModPlatform {
+    Fabric,
+    Quilt,
+    Forge,
+    NeoForge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    Required,
+    Optional,
+    Incompatible,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModDependency {
+    pub id: String,
+    pub versions: Option<String>, // as declared, e.g. ">=0.15" or "[47,)"
+    pub kind: DependencyKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvidedMod {
+    pub id: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModInfo {
+    pub file_name: String,
+    pub size: u64,
+    pub platform: Option<ModPlatform>, // None when the jar has no metadata
+    pub id: Option<String>,
+    pub name: String, // the file name when the jar has no metadata
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    pub environment: ModEnvironment,
+    pub provides: Vec<ProvidedMod>, // other mods this jar satisfies, bundled ones included
+    pub dependencies: Vec<ModDependency>,
+    pub icon: Option<String>, // data URL
+}
+
+impl ModInfo {
+    fn unknown(file_name: &str, size: u64) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            size,
+            platform: None,
+            id: None,
+            name: file_name.to_string(),
+            version: None,
+            description: None,
+            authors: Vec::new(),
+            environment: ModEnvironment::Both,
+            provides: Vec::new(),
+            dependencies: Vec::new(),
+            icon: None,
+        }
+    }
+}
+
+fn read_text<R: Read + Seek>(jar: &mut ZipArchive<R>, name: &str) -> Option<String> {
+    let mut entry = jar.by_name(name).ok()?;
+    let mut text = String::new();
+    entry.read_to_string(&mut text).ok()?;
+    Some(text)
+}
+
+fn read_icon<R: Read + Seek>(jar: &mut ZipArchive<R>, name: &str) -> Option<String> {
+    let name = name.trim_start_matches('/');
+    let mut entry = jar.by_name(name).ok()?;
+
+    if entry.size() as usize > MAX_ICON_BYTES {
+        return None;
+    }
+
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).ok()?;
+
+    let mime = if name.ends_with(".jpg") || name.ends_with(".jpeg") {
+        "image/jpeg"
+    } else {
+        "image/png"
+    };
+
+    Some(format!("data:{};base64,{}", mime, STANDARD.encode(bytes)))
+}
+
+fn str_of(value: &Value) -> Option<String> {
+    value.as_str().map(String::from)
+}
+
+/// Version predicates are a string or a list of alternatives
+fn versions_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(list) => Some(
+            list.iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(" || "),
+        ),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Fabric
+
+fn fabric_dependencies(meta: &Value) -> Vec<ModDependency> {
+    let sections = [
+        ("depends", DependencyKind::Required),
+        ("recommends", DependencyKind::Optional),
+        ("suggests", DependencyKind::Optional),
+        ("breaks", DependencyKind::Incompatible),
+    ];
+
+    sections
+        .iter()
+        .flat_map(|(key, kind)| {
+            meta[key]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(move |(id, versions)| ModDependency {
+                    id: id.clone(),
+                    versions: versions_of(versions),
+                    kind: *kind,
+                })
+        })
+        .collect()
+}
+
+fn read_fabric<R: Read + Seek>(
+    jar: &mut ZipArchive<R>,
+    text: &str,
+    icons: bool,
+    info: &mut ModInfo,
+) -> Option<()> {
+    let meta: Value = serde_json::from_str(text).ok()?;
+
+    info.platform = Some(ModPlatform::Fabric);
+    info.id = str_of(&meta["id"]);
+    info.version = str_of(&meta["version"]);
+    info.description = str_of(&meta["description"]);
+    info.name = str_of(&meta["name"]).or(info.id.clone())?;
+
+    // Authors are names or { "name": ... } objects
+    info.authors = meta["authors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|a| str_of(a).or_else(|| str_of(&a["name"])))
+        .collect();
+
+    info.environment = match meta["environment"].as_str() {
+        Some("client") => ModEnvironment::Client,
+        Some("server") => ModEnvironment::Server,
+        _ => ModEnvironment::Both,
+    };
+
+    info.provides = meta["provides"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(str_of)
+        .map(|id| ProvidedMod {
+            id,
+            version: info.version.clone(),
+        })
+        .collect();
+
+    info.dependencies = fabric_dependencies(&meta);
+
+    // A path, or paths keyed by size where the largest is best
+    let icon = match &meta["icon"] {
+        Value::Object(sizes) => sizes
+            .iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+            .and_then(|(_, path)| str_of(path)),
+        other => str_of(other),
+    };
+    info.icon = icon
+        .filter(|_| icons)
+        .and_then(|icon| read_icon(jar, &icon));
+
+    Some(())
+}
+
+/// Quilt
+
+fn quilt_dependency(entry: &Value, kind: DependencyKind) -> Option<ModDependency> {
+    if let Some(id) = entry.as_str() {
+        return Some(ModDependency {
+            id: id.to_string(),
+            versions: None,
+            kind,
+        });
+    }
+
+    let optional = entry["optional"].as_bool().unwrap_or(false);
+
+    Some(ModDependency {
+        id: str_of(&entry["id"])?,
+        versions: versions_of(&entry["versions"]),
+        kind: if kind == DependencyKind::Required && optional {
+            DependencyKind::Optional
+        } else {
+            kind
+        },
+    })
+}
+
+fn read_quilt<R: Read + Seek>(
+    jar: &mut ZipArchive<R>,
+    text: &str,
+    icons: bool,
+    info: &mut ModInfo,
+) -> Option<()> {
+    let meta: Value = serde_json::from_str(text).ok()?;
+    let loader = &meta["quilt_loader"];
+    let metadata = &loader["metadata"];
+
+    info.platform = Some(ModPlatform::Quilt);
+    info.id = str_of(&loader["id"]);
+    info.version = str_of(&loader["version"]);
+    info.description = str_of(&metadata["description"]);
+    info.name = str_of(&metadata["name"]).or(info.id.clone())?;
+
+    // { "Name": "Owner", ... }
+    info.authors = metadata["contributors"]
+        .as_object()
+        .map(|c| c.keys().cloned().collect())
+        .unwrap_or_default();
+
+    info.environment = match meta["minecraft"]["environment"].as_str() {
+        Some("client") => ModEnvironment::Client,
+        Some("dedicated_server") => ModEnvironment::Server,
+        _ => ModEnvironment::Both,
+    };
+
+    info.provides = loader["provides"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| {
+            Some(ProvidedMod {
+                id: str_of(p).or_else(|| str_of(&p["id"]))?,
+                version: str_of(&p["version"]).or(info.version.clone()),
+            })
+        })
+        .collect();
+
+    let list = |key: &str, kind: DependencyKind| {
+        loader[key]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|d| quilt_dependency(d, kind))
+            .collect::<Vec<_>>()
+    };
+
+    info.dependencies = list("depends", DependencyKind::Required);
+    info.dependencies
+        .extend(list("breaks", DependencyKind::Incompatible));
+
+    info.icon = str_of(&metadata["icon"])
+        .filter(|_| icons)
+        .and_then(|icon| read_icon(jar, &icon));
+
+    Some(())
+}
+
+/// Forge and NeoForge
+
+fn manifest_version<R: Read + Seek>(jar: &mut ZipArchive<R>) -> Option<String> {
+    read_text(jar, "META-INF/MANIFEST.MF")?
+        .lines()
+        .find_map(|line| line.strip_prefix("Implementation-Version:"))
+        .map(|v| v.trim().to_string())
+}
+
+fn toml_str(table: &toml::Table, key: &str) -> Option<String> {
+    table.get(key)?.as_str().map(String::from)
+}
+
+fn forge_dependency(entry: &toml::Table) -> Option<ModDependency> {
+    // NeoForge uses `type`, Forge the older `mandatory` flag
+    let mut kind = match toml_str(entry, "type").as_deref() {
+        Some("required") => DependencyKind::Required,
+        Some("incompatible") => DependencyKind::Incompatible,
+        Some(_) => DependencyKind::Optional,
+        None => match entry.get("mandatory").and_then(|m| m.as_bool()) {
+            Some(false) => DependencyKind::Optional,
+            _ => DependencyKind::Required,
+        },
+    };
+
+    // Client-side dependencies aren't needed on a server
+    if kind == DependencyKind::Required && toml_str(entry, "side").as_deref() == Some("CLIENT") {
+        kind = DependencyKind::Optional;
+    }
+
+    Some(ModDependency {
+        id: toml_str(entry, "modId")?,
+        versions: toml_str(entry, "versionRange"),
+        kind,
+    })
+}
+
+fn read_forge<R: Read + Seek>(
+    jar: &mut ZipArchive<R>,
+    text: &str,
+    neoforge_toml: bool,
+    icons: bool,
+    info: &mut ModInfo,
+) -> Option<()> {
+    let meta: toml::Table = text.parse().ok()?;
+    let mods = meta.get("mods")?.as_array()?;
+    let first = mods.first()?.as_table()?;
+
+    let id = toml_str(first, "modId")?;
+
+    info.id = Some(id.clone());
+    info.name = toml_str(first, "displayName").unwrap_or(id.clone());
+    info.description = toml_str(first, "description").map(|d| d.trim().to_string());
+
+    info.version = match toml_str(first, "version") {
+        Some(v) if v.contains("${file.jarVersion}") => manifest_version(jar),
+        other => other,
+    };
+
+    // A comma separated string, sometimes a list
+    info.authors = match meta.get("authors").or_else(|| first.get("authors")) {
+        Some(toml::Value::String(s)) => s.split(',').map(|a| a.trim().to_string()).collect(),
+        Some(toml::Value::Array(list)) => list
+            .iter()
+            .filter_map(|a| a.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    };
+    info.authors.retain(|a| !a.is_empty());
+
+    // Mods clients can join without say so through displayTest
+    let display_test = toml_str(first, "displayTest").or_else(|| toml_str(&meta, "displayTest"));
+    let client_only = meta.get("clientSideOnly").and_then(|v| v.as_bool()) == Some(true);
+
+    info.environment = if client_only {
+        ModEnvironment::Client
+    } else if display_test.as_deref() == Some("IGNORE_SERVER_VERSION") {
+        ModEnvironment::Server
+    } else {
+        ModEnvironment::Both
+    };
+
+    // Further [[mods]] entries ship in the same jar
+    info.provides = mods
+        .iter()
+        .skip(1)
+        .filter_map(|m| m.as_table())
+        .filter_map(|m| {
+            Some(ProvidedMod {
+                id: toml_str(m, "modId")?,
+                version: toml_str(m, "version").filter(|v| !v.starts_with("${")),
+            })
+        })
+        .collect();
+
+    info.dependencies = meta
+        .get("dependencies")
+        .and_then(|d| d.as_table())
+        .map(|deps| {
+            deps.values()
+                .filter_map(|list| list.as_array())
+                .flatten()
+                .filter_map(|entry| entry.as_table().and_then(forge_dependency))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // NeoForge for 1.20.2 to 1.20.4 still used mods.toml
+    let on_neoforge = neoforge_toml || info.dependencies.iter().any(|d| d.id == "neoforge");
+    info.platform = Some(if on_neoforge {
+        ModPlatform::NeoForge
+    } else {
+        ModPlatform::Forge
+    });
+
+    info.icon = toml_str(first, "logoFile")
+        .or_else(|| toml_str(&meta, "logoFile"))
+        .filter(|_| icons)
+        .and_then(|logo| read_icon(jar, &logo));
+
+    Some(())
+}
+
+/// Legacy Forge
+
+fn read_mcmod_info<R: Read + Seek>(
+    jar: &mut ZipArchive<R>,
+    text: &str,
+    icons: bool,
+    info: &mut ModInfo,
+) -> Option<()> {
+    let meta: Value = serde_json::from_str(text).ok()?;
+
+    // A bare list, or { "modListVersion": 2, "modList": [...] }
+    let list = meta.as_array().or_else(|| meta["modList"].as_array())?;
+    let first = list.first()?;
+
+    info.platform = Some(ModPlatform::Forge);
+    info.id = str_of(&first["modid"]);
+    info.name = str_of(&first["name"]).or(info.id.clone())?;
+    info.version = str_of(&first["version"]);
+    info.description = str_of(&first["description"]);
+
+    info.authors = first["authorList"]
+        .as_array()
+        .or_else(|| first["authors"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(str_of)
+        .collect();
+
+    info.provides = list
+        .iter()
+        .skip(1)
+        .filter_map(|m| {
+            Some(ProvidedMod {
+                id: str_of(&m["modid"])?,
+                version: str_of(&m["version"]),
+            })
+        })
+        .collect();
+
+    // "Forge@[10.13,)" style entries
+    info.dependencies = first["requiredMods"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d.as_str())
+        .map(|d| {
+            let (id, versions) = match d.split_once('@') {
+                Some((id, versions)) => (id, Some(versions.to_string())),
+                None => (d, None),
+            };
+
+            ModDependency {
+                id: id.to_lowercase(),
+                versions,
+                kind: DependencyKind::Required,
+            }
+        })
+        .collect();
+
+    info.icon = str_of(&first["logoFile"])
+        .filter(|_| icons)
+        .and_then(|logo| read_icon(jar, &logo));
+
+    Some(())
+}
+
+/// Mods bundled in `jar`, with whatever they bundle in turn
+fn nested_mods<R: Read + Seek>(jar: &mut ZipArchive<R>, depth: u32) -> Vec<ProvidedMod> {
+    if depth >= MAX_NESTING {
+        return Vec::new();
+    }
+
+    let names: Vec<String> = jar
+        .file_names()
+        .filter(|n| n.ends_with(".jar") && NESTED_JAR_DIRS.iter().any(|d| n.starts_with(d)))
+        .map(String::from)
+        .collect();
+
+    let mut provided = Vec::new();
+
+    for name in names {
+        let mut bytes = Vec::new();
+        let read = jar
+            .by_name(&name)
+            .ok()
+            .and_then(|mut entry| entry.read_to_end(&mut bytes).ok());
+
+        let Some(mut nested) = read.and_then(|_| ZipArchive::new(Cursor::new(bytes)).ok()) else {
+            continue;
+        };
+
+        // Only what a bundled mod provides is kept, never its icon
+        let info = read_archive(&mut nested, &name, 0, depth + 1, false);
+
+        if let Some(id) = info.id {
+            provided.push(ProvidedMod {
+                id,
+                version: info.version,
+            });
+        }
+        provided.extend(info.provides);
+    }
+
+    provided
+}
+
+fn read_archive<R: Read + Seek>(
+    jar: &mut ZipArchive<R>,
+    file_name: &str,
+    size: u64,
+    depth: u32,
+    icons: bool,
+) -> ModInfo {
+    let mut info = ModInfo::unknown(file_name, size);
+
+    let parsed = if let Some(text) = read_text(jar, "fabric.mod.json") {
+        read_fabric(jar, &text, icons, &mut info)
+    } else if let Some(text) = read_text(jar, "quilt.mod.json") {
+        read_quilt(jar, &text, icons, &mut info)
+    } else if let Some(text) = read_text(jar, "META-INF/neoforge.mods.toml") {
+        read_forge(jar, &text, true, icons, &mut info)
+    } else if let Some(text) = read_text(jar, "META-INF/mods.toml") {
+        read_forge(jar, &text, false, icons, &mut info)
+    } else if let Some(text) = read_text(jar, "mcmod.info") {
+        read_mcmod_info(jar, &text, icons, &mut info)
+    } else {
+        None
+    };
+
+    // Half-read metadata is worse than none
+    if parsed.is_none() {
+        info = ModInfo::unknown(file_name, size);
+    }
+
+    // Bundled libraries count even when the outer jar has no metadata
+    let nested = nested_mods(jar, depth);
+    info.provides.extend(nested);
+
+    info
+}
+
+/// Reads a jar's metadata, falling back to its file name. Icons are only
+/// read for listings, checks don't need them.
+pub fn read_mod_jar(path: &Path, icons: bool) -> ModInfo {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+
+    match fs::File::open(path)
+        .ok()
+        .and_then(|f| ZipArchive::new(f).ok())
+    {
+        Some(mut jar) => read_archive(&mut jar, &file_name, size, 0, icons),
+        None => ModInfo::unknown(&file_name, size),
+    }
+}
+
+/// Every jar in `dir`, sorted by name
+pub fn scan_mods_dir(dir: &Path, icons: bool) -> Vec<ModInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut mods: Vec<ModInfo> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "jar"))
+        .map(|p| read_mod_jar(&p, icons))
+        .collect();
+
+    mods.sort_by_key(|m| m.name.to_lowercase());
+
+    mods
+}
+
+#[tauri::command]
+pub async fn list_mods(server_id: String) -> Result<Vec<ModInfo>, String> {
+    let server = find_server(&server_id)?;
+    let dir = PathBuf::from(&server.path).join("mods");
+
+    // Opening every jar takes a while on big packs, keep it off the main thread
+    tauri::async_runtime::spawn_blocking(move || scan_mods_dir(&dir, true))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,342 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::modrinth_browser::content_target;
+use crate::commands::server_management::{ensure_stopped, find_server, ServerConfig};
+use crate::state::app_state::AppState;
+
+/// TOGGLING MODS AND MOD PROFILES
+///
+/// A disabled jar stays next to the others as `<name>.jar.disabled`, or is
+/// moved into a sibling `mods-disabled/` (`plugins-disabled/` on Paper)
+/// folder. Loaders only pick up `*.jar`, so either way it is skipped.
+/// Profiles are named sets of enabled jars, kept in the server's
+/// `cubely-mod-profiles.json`; applying one enables exactly those jars.
+
+const PROFILES_FILE: &str = "cubely-mod-profiles.json";
+const DISABLED_SUFFIX: &str = ".disabled";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisableMethod {
+    #[default]
+    Rename, // mods/<name>.jar.disabled
+    Folder, // mods-disabled/<name>.jar
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModFileState {
+    pub file_name: String, // always the `.jar` name
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModProfile {
+    pub name: String,
+    pub enabled: Vec<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModProfiles {
+    #[serde(default)]
+    pub profiles: Vec<ModProfile>,
+}
+
+impl ModProfiles {
+    fn load(server_path: &Path) -> Self {
+        fs::read_to_string(server_path.join(PROFILES_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, server_path: &Path) -> Result<(), String> {
+        fs::write(
+            server_path.join(PROFILES_FILE),
+            serde_json::to_string_pretty(self).unwrap(),
+        )
+        .map_err(|e| format!("Failed to save mod profiles: {}", e))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileApplyResult {
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    pub missing: Vec<String>, // in the profile but no longer on disk
+}
+
+/// `mods/` or `plugins/` of a server
+pub fn content_dir(server: &ServerConfig) -> Result<PathBuf, String> {
+    let (folder, _) = content_target(&server.loader)?;
+    Ok(PathBuf::from(&server.path).join(folder))
+}
+
+fn disabled_dir(dir: &Path) -> PathBuf {
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    dir.with_file_name(format!("{}-disabled", name))
+}
+
+fn jar_names(dir: &Path, suffix: &str) -> Vec<String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let jar = name.strip_suffix(suffix)?;
+            jar.ends_with(".jar").then(|| jar.to_string())
+        })
+        .collect()
+}
+
+/// Every jar in `dir`, enabled or not, sorted by name
+pub fn jar_states(dir: &Path) -> Vec<ModFileState> {
+    let mut states: Vec<ModFileState> = jar_names(dir, "")
+        .into_iter()
+        .map(|file_name| ModFileState {
+            file_name,
+            enabled: true,
+        })
+        .collect();
+
+    let disabled = jar_names(dir, DISABLED_SUFFIX)
+        .into_iter()
+        .chain(jar_names(&disabled_dir(dir), ""));
+
+    for file_name in disabled {
+        // A jar both enabled and disabled is enabled as far as the loader cares
+        if !states.iter().any(|s| s.file_name == file_name) {
+            states.push(ModFileState {
+                file_name,
+                enabled: false,
+            });
+        }
+    }
+
+    states.sort_by_key(|s| s.file_name.to_lowercase());
+    states
+}
+
+/// Where `file_name` is on disk right now, enabled or not
+pub fn jar_location(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    [
+        dir.join(file_name),
+        dir.join(format!("{}{}", file_name, DISABLED_SUFFIX)),
+        disabled_dir(dir).join(file_name),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
+pub fn enabled_jars(dir: &Path) -> Vec<String> {
+    jar_states(dir)
+        .into_iter()
+        .filter(|s| s.enabled)
+        .map(|s| s.file_name)
+        .collect()
+}
+
+fn check_jar_name(file_name: &str) -> Result<(), String> {
+    if file_name.contains(['/', '\\']) || file_name.starts_with('.') || !file_name.ends_with(".jar")
+    {
+        return Err(format!("Invalid jar {}", file_name));
+    }
+
+    Ok(())
+}
+
+/// Enables or disables `file_name` (the `.jar` name) in `dir`, a no-op when it already is
+pub fn set_jar_enabled(
+    dir: &Path,
+    file_name: &str,
+    enabled: bool,
+    method: DisableMethod,
+) -> Result<(), String> {
+    check_jar_name(file_name)?;
+
+    let active = dir.join(file_name);
+    let renamed = dir.join(format!("{}{}", file_name, DISABLED_SUFFIX));
+    let moved = disabled_dir(dir).join(file_name);
+
+    let result = if enabled {
+        if active.exists() {
+            return Ok(());
+        }
+
+        let from = [&renamed, &moved]
+            .into_iter()
+            .find(|p| p.exists())
+            .ok_or(format!("{} not found", file_name))?;
+
+        fs::rename(from, &active)
+    } else {
+        if !active.exists() {
+            return match renamed.exists() || moved.exists() {
+                true => Ok(()),
+                false => Err(format!("{} not found", file_name)),
+            };
+        }
+
+        match method {
+            DisableMethod::Rename => fs::rename(&active, &renamed),
+            DisableMethod::Folder => {
+                fs::create_dir_all(disabled_dir(dir)).and_then(|_| fs::rename(&active, &moved))
+            }
+        }
+    };
+
+    result.map_err(|e| format!("Failed to toggle {}: {}", file_name, e))
+}
+
+/// Enables exactly the jars in `enabled`, returning what changed and what is missing
+pub fn apply_enabled_set(dir: &Path, enabled: &[String]) -> Result<ProfileApplyResult, String> {
+    let states = jar_states(dir);
+    let mut result = ProfileApplyResult {
+        enabled: Vec::new(),
+        disabled: Vec::new(),
+        missing: Vec::new(),
+    };
+
+    for state in &states {
+        let wanted = enabled.contains(&state.file_name);
+
+        if wanted != state.enabled {
+            set_jar_enabled(dir, &state.file_name, wanted, DisableMethod::Rename)?;
+
+            match wanted {
+                true => result.enabled.push(state.file_name.clone()),
+                false => result.disabled.push(state.file_name.clone()),
+            }
+        }
+    }
+
+    result.missing = enabled
+        .iter()
+        .filter(|name| !states.iter().any(|s| &s.file_name == *name))
+        .cloned()
+        .collect();
+
+    Ok(result)
+}
+
+/// Commands
+
+#[tauri::command]
+pub fn list_mod_files(server_id: String) -> Result<Vec<ModFileState>, String> {
+    let server = find_server(&server_id)?;
+    Ok(jar_states(&content_dir(&server)?))
+}
+
+#[tauri::command]
+pub fn set_mod_enabled(
+    server_id: String,
+    file_name: String,
+    enabled: bool,
+    method: Option<DisableMethod>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    ensure_stopped(&server_id, &state, "Stop the server before toggling mods")?;
+
+    let server = find_server(&server_id)?;
+    set_jar_enabled(
+        &content_dir(&server)?,
+        &file_name,
+        enabled,
+        method.unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+pub fn list_mod_profiles(server_id: String) -> Result<Vec<ModProfile>, String> {
+    let server = find_server(&server_id)?;
+    Ok(ModProfiles::load(Path::new(&server.path)).profiles)
+}
+
+/// Saves `enabled` as a profile, or the jars enabled right now when it is None
+#[tauri::command]
+pub fn save_mod_profile(
+    server_id: String,
+    name: String,
+    enabled: Option<Vec<String>>,
+) -> Result<ModProfile, String> {
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".into());
+    }
+
+    let enabled = match enabled {
+        Some(enabled) => {
+            for file_name in &enabled {
+                check_jar_name(file_name)?;
+            }
+            enabled
+        }
+        None => enabled_jars(&content_dir(&server)?),
+    };
+
+    let profile = ModProfile {
+        name,
+        enabled,
+        created_at: Utc::now().timestamp(),
+    };
+
+    let mut profiles = ModProfiles::load(&server_path);
+    profiles.profiles.retain(|p| p.name != profile.name);
+    profiles.profiles.push(profile.clone());
+    profiles.save(&server_path)?;
+
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn delete_mod_profile(server_id: String, name: String) -> Result<(), String> {
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+
+    let mut profiles = ModProfiles::load(&server_path);
+    let before = profiles.profiles.len();
+    profiles.profiles.retain(|p| p.name != name);
+
+    if profiles.profiles.len() == before {
+        return Err("Profile not found".into());
+    }
+
+    profiles.save(&server_path)
+}
+
+#[tauri::command]
+pub fn apply_mod_profile(
+    server_id: String,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProfileApplyResult, String> {
+    ensure_stopped(
+        &server_id,
+        &state,
+        "Stop the server before switching mod profiles",
+    )?;
+
+    let server = find_server(&server_id)?;
+    let profile = ModProfiles::load(Path::new(&server.path))
+        .profiles
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or("Profile not found")?;
+
+    apply_enabled_set(&content_dir(&server)?, &profile.enabled)
+}
@@ -0,0 +1,78 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// MOD PROVENANCE
+///
+/// Where each file Cubely installed into `mods/` or `plugins/` came from, kept in the
+/// server's `cubely-mods.json` so it can be updated or removed later.
+/// Jars dropped in by hand have no entry.
+
+const SOURCES_FILE: &str = "cubely-mods.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModProvider {
+    Modrinth,
+    Hangar,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledFile {
+    pub folder: String, // relative to the server, "mods" or "plugins"
+    pub file_name: String,
+    pub provider: ModProvider,
+    pub project_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub sha1: String,
+    pub installed_at: i64,
+
+    // Pulled in as a dependency rather than picked
+    #[serde(default)]
+    pub dependency: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModSources {
+    #[serde(default)]
+    pub files: Vec<InstalledFile>,
+}
+
+impl ModSources {
+    pub fn load(server_path: &Path) -> Self {
+        fs::read_to_string(server_path.join(SOURCES_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, server_path: &Path) -> Result<(), String> {
+        fs::write(
+            server_path.join(SOURCES_FILE),
+            serde_json::to_string_pretty(self).unwrap(),
+        )
+        .map_err(|e| format!("Failed to save mod sources: {}", e))
+    }
+
+    pub fn find(&self, folder: &str, file_name: &str) -> Option<&InstalledFile> {
+        self.files
+            .iter()
+            .find(|f| f.folder == folder && f.file_name == file_name)
+    }
+
+    pub fn has_project(&self, project_id: &str) -> bool {
+        self.files.iter().any(|f| f.project_id == project_id)
+    }
+
+    /// Replaces the entry for the same file, if any
+    pub fn record(&mut self, file: InstalledFile) {
+        self.forget(&file.folder, &file.file_name);
+        self.files.push(file);
+    }
+
+    pub fn forget(&mut self, folder: &str, file_name: &str) {
+        self.files
+            .retain(|f| !(f.folder == folder && f.file_name == file_name));
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha1::Sha1;
+
+use crate::commands::artifact_cache::cached_download;
+use crate::commands::mod_sources::{InstalledFile, ModProvider, ModSources};
+use crate::commands::modrinth_browser::{content_target, versions_by_hash, ModrinthVersion};
+use crate::commands::server_management::{ensure_stopped, find_server, ServerConfig};
+use crate::state::app_state::AppState;
+use crate::utils::download::{hash_file, Checksum, DownloadEvents};
+use crate::utils::network::{endpoint, http_client, Endpoint};
+use crate::utils::path::backups_dir;
+
+/// UPDATING MODS AND PLUGINS
+///
+/// Looks up every jar in `mods/` (or `plugins/` on Paper) on Modrinth by its
+/// sha1 and offers the newest version for the server's game version and
+/// loader. Updating moves the old jars into a backup first and leaves a
+/// marker in the server directory: if the next launch dies before the server
+/// is up, the update is rolled back on its own, otherwise it is kept.
+
+pub const PENDING_FILE: &str = "cubely-mod-update.json";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModUpdate {
+    pub folder: String,
+    pub file_name: String,
+    pub project_id: String,
+    pub current_version: String,
+    pub latest_version: String,
+    pub latest_version_id: String,
+    pub latest_file_name: String,
+    pub version_type: String, // release, beta or alpha
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplacedMod {
+    pub old_file: String,
+    pub new_file: String,
+}
+
+/// The last update, until a launch shows whether it works
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingModUpdate {
+    pub folder: String,
+    pub backup_path: String,
+    pub replaced: Vec<ReplacedMod>,
+    pub applied_at: i64,
+
+    // Provenance before the update, restored on rollback
+    pub sources: ModSources,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModUpdateResult {
+    pub updated: Vec<ReplacedMod>,
+    pub backup_path: String,
+}
+
+/// Jars in `dir` keyed by sha1
+fn hash_jars(dir: &Path) -> HashMap<String, String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "jar"))
+        .filter_map(|p| {
+            let sha1 = hash_file::<Sha1>(&p).ok()?;
+            Some((sha1, p.file_name()?.to_string_lossy().to_string()))
+        })
+        .collect()
+}
+
+/// Newest version for each hash that runs on `loaders` and `game_version`, keyed by hash
+async fn latest_versions(
+    client: &Client,
+    hashes: Vec<String>,
+    loaders: &[&str],
+    game_version: &str,
+) -> Result<HashMap<String, ModrinthVersion>, String> {
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let url = format!("{}/v2/version_files/update", endpoint(Endpoint::Modrinth));
+
+    let response = client
+        .post(url)
+        .json(&json!({
+            "hashes": hashes,
+            "algorithm": "sha1",
+            "loaders": loaders,
+            "game_versions": [game_version],
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Modrinth: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Modrinth returned {}", response.status()));
+    }
+
+    response.json().await.map_err(|e| e.to_string())
+}
+
+/// Available updates, each with the version it updates to
+async fn find_updates(
+    client: &Client,
+    server: &ServerConfig,
+) -> Result<Vec<(ModUpdate, ModrinthVersion)>, String> {
+    let (folder, loaders) = content_target(&server.loader)?;
+    let jars = hash_jars(&PathBuf::from(&server.path).join(folder));
+    let hashes: Vec<String> = jars.keys().cloned().collect();
+
+    let current = versions_by_hash(client, hashes.clone()).await?;
+    let latest = latest_versions(client, hashes, loaders, &server.version).await?;
+
+    let mut updates = Vec::new();
+
+    for (sha1, version) in latest {
+        let (Some(file_name), Some(installed)) = (jars.get(&sha1), current.get(&sha1)) else {
+            continue;
+        };
+
+        // The newest build for this game version can be older than a jar
+        // someone put in by hand, only ever move forward
+        if version.id == installed.id || version.date_published <= installed.date_published {
+            continue;
+        }
+
+        let Some(file) = version.primary_file() else {
+            continue;
+        };
+
+        updates.push((
+            ModUpdate {
+                folder: folder.to_string(),
+                file_name: file_name.clone(),
+                project_id: version.project_id.clone(),
+                current_version: installed.version_number.clone(),
+                latest_version: version.version_number.clone(),
+                latest_version_id: version.id.clone(),
+                latest_file_name: file.filename.clone(),
+                version_type: version.version_type.clone(),
+            },
+            version,
+        ));
+    }
+
+    updates.sort_by_key(|(u, _)| u.file_name.to_lowercase());
+
+    Ok(updates)
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    // Backups can live on another drive than the server
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to).map_err(|e| e.to_string())?;
+        fs::remove_file(from).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Puts the replaced jars back, best effort so one bad file doesn't strand the rest
+fn restore_jars(dir: &Path, backup: &Path, replaced: &[ReplacedMod]) -> Vec<String> {
+    let mut failed = Vec::new();
+
+    for file in replaced {
+        fs::remove_file(dir.join(&file.new_file)).ok();
+
+        if let Err(e) = fs::copy(backup.join(&file.old_file), dir.join(&file.old_file)) {
+            eprintln!("Failed to restore {}: {}", file.old_file, e);
+            failed.push(file.old_file.clone());
+        }
+    }
+
+    failed
+}
+
+fn load_pending(server_path: &Path) -> Option<PendingModUpdate> {
+    fs::read_to_string(server_path.join(PENDING_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+/// Keeps the pending update, called once the server reports it is up
+pub fn confirm_mod_update(server_path: &Path) {
+    fs::remove_file(server_path.join(PENDING_FILE)).ok();
+}
+
+/// Undoes the pending update, returning the jars that were restored
+pub fn rollback_mod_update(server_path: &Path) -> Result<Vec<String>, String> {
+    let pending = load_pending(server_path).ok_or("No mod update to roll back")?;
+
+    let dir = server_path.join(&pending.folder);
+    let failed = restore_jars(&dir, Path::new(&pending.backup_path), &pending.replaced);
+
+    pending.sources.save(server_path)?;
+    fs::remove_file(server_path.join(PENDING_FILE)).ok();
+
+    if !failed.is_empty() {
+        return Err(format!(
+            "Failed to restore {}, the originals are in {}",
+            failed.join(", "),
+            pending.backup_path
+        ));
+    }
+
+    Ok(pending.replaced.into_iter().map(|f| f.old_file).collect())
+}
+
+/// Commands
+
+#[tauri::command]
+pub async fn check_mod_updates(server_id: String) -> Result<Vec<ModUpdate>, String> {
+    let server = find_server(&server_id)?;
+    let updates = find_updates(&http_client(), &server).await?;
+
+    Ok(updates.into_iter().map(|(update, _)| update).collect())
+}
+
+/// Updates the given jars, or every jar with an update when `file_names` is None
+#[tauri::command]
+pub async fn apply_mod_updates(
+    server_id: String,
+    file_names: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModUpdateResult, String> {
+    ensure_stopped(&server_id, &state, "Stop the server before updating mods")?;
+
+    let app = {
+        let guard = state.app_handle.lock().unwrap();
+        guard.clone().ok_or("App handle not initialized")?
+    };
+
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+    let client = http_client();
+
+    let updates: Vec<(ModUpdate, ModrinthVersion)> = find_updates(&client, &server)
+        .await?
+        .into_iter()
+        .filter(|(u, _)| {
+            file_names
+                .as_ref()
+                .map_or(true, |names| names.contains(&u.file_name))
+        })
+        .collect();
+
+    if updates.is_empty() {
+        return Err("No updates to apply".into());
+    }
+
+    let folder = updates[0].0.folder.clone();
+    let dir = server_path.join(&folder);
+
+    let backup = backups_dir().join(&server.id).join(format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        folder
+    ));
+    fs::create_dir_all(&backup).map_err(|e| format!("Failed to back up {}: {}", folder, e))?;
+
+    let sources = ModSources::load(&server_path);
+    let mut updated_sources = sources.clone();
+    let mut replaced: Vec<ReplacedMod> = Vec::new();
+
+    for (update, version) in &updates {
+        let result = async {
+            let file = version
+                .primary_file()
+                .ok_or(format!("{} has no files", version.name))?;
+            let sha1 = file
+                .hashes
+                .get("sha1")
+                .ok_or(format!("{} has no checksum", file.filename))?;
+
+            // Names come from the API, keep them inside the folder
+            if file.filename.contains(['/', '\\']) || file.filename.starts_with('.') {
+                return Err(format!("Invalid file name {}", file.filename));
+            }
+
+            move_file(
+                &dir.join(&update.file_name),
+                &backup.join(&update.file_name),
+            )?;
+
+            let progress = DownloadEvents::new(app.clone(), file.filename.clone());
+            let downloaded = cached_download(
+                &client,
+                &file.url,
+                &dir.join(&file.filename),
+                Some(&Checksum::Sha1(sha1.clone())),
+                &progress,
+            )
+            .await;
+
+            if let Err(e) = downloaded {
+                fs::copy(backup.join(&update.file_name), dir.join(&update.file_name)).ok();
+                return Err(e);
+            }
+
+            let dependency = sources
+                .find(&folder, &update.file_name)
+                .is_some_and(|f| f.dependency);
+
+            updated_sources.forget(&folder, &update.file_name);
+            updated_sources.record(InstalledFile {
+                folder: folder.clone(),
+                file_name: file.filename.clone(),
+                provider: ModProvider::Modrinth,
+                project_id: version.project_id.clone(),
+                version_id: version.id.clone(),
+                version_number: version.version_number.clone(),
+                sha1: sha1.clone(),
+                installed_at: Utc::now().timestamp(),
+                dependency,
+            });
+
+            Ok(file.filename.clone())
+        }
+        .await;
+
+        match result {
+            Ok(new_file) => replaced.push(ReplacedMod {
+                old_file: update.file_name.clone(),
+                new_file,
+            }),
+            Err(e) => {
+                // Half an update is worse than none, mods often move together
+                restore_jars(&dir, &backup, &replaced);
+                return Err(format!("Failed to update {}: {}", update.file_name, e));
+            }
+        }
+    }
+
+    updated_sources.save(&server_path)?;
+
+    let pending = PendingModUpdate {
+        folder,
+        backup_path: backup.to_string_lossy().to_string(),
+        replaced: replaced.clone(),
+        applied_at: Utc::now().timestamp(),
+        sources,
+    };
+
+    fs::write(
+        server_path.join(PENDING_FILE),
+        serde_json::to_string_pretty(&pending).unwrap(),
+    )
+    .map_err(|e| format!("Failed to save mod update: {}", e))?;
+
+    Ok(ModUpdateResult {
+        updated: replaced,
+        backup_path: pending.backup_path,
+    })
+}
+
+#[tauri::command]
+pub fn get_pending_mod_update(server_id: String) -> Result<Option<PendingModUpdate>, String> {
+    let server = find_server(&server_id)?;
+    Ok(load_pending(Path::new(&server.path)))
+}
+
+#[tauri::command]
+pub fn rollback_mod_updates(
+    server_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    ensure_stopped(
+        &server_id,
+        &state,
+        "Stop the server before rolling back mods",
+    )?;
+
+    let server = find_server(&server_id)?;
+    rollback_mod_update(Path::new(&server.path))
+}
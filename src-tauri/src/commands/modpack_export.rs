@@ -0,0 +1,248 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::Sha512;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::commands::mod_inventory::{read_mod_jar, ModEnvironment};
+use crate::commands::modrinth_browser::versions_by_hash;
+use crate::commands::modpack_import::{MrPackEnv, MrPackFile, MrPackIndex, INDEX_FILE};
+use crate::commands::server_creation::LoaderType;
+use crate::commands::server_management::{find_server, ServerConfig};
+use crate::utils::download::hash_file;
+use crate::utils::network::http_client;
+use crate::utils::path::{exports_dir, server_slug};
+
+/// EXPORTING MODPACKS
+///
+/// Turns a server's `mods/` into a `.mrpack` players can import in their
+/// launcher. Jars Modrinth knows by hash are referenced by download URL,
+/// anything else is embedded as an override. A plain zip of the mods players
+/// need is written next to it for launchers without `.mrpack` support.
+
+struct ModJar {
+    path: PathBuf,
+    file_name: String,
+    sha1: String,
+    sha512: String,
+    size: u64,
+    side: ModEnvironment,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModpackExport {
+    pub mrpack_path: String,
+    pub client_zip_path: String,
+    pub linked: Vec<String>,      // referenced by Modrinth download URL
+    pub embedded: Vec<String>,    // unknown to Modrinth, shipped inside the pack
+    pub server_only: Vec<String>, // left out of the client zip
+}
+
+fn scan_mods(server: &ServerConfig) -> Result<Vec<ModJar>, String> {
+    let mods_dir = PathBuf::from(&server.path).join("mods");
+    let entries = fs::read_dir(&mods_dir).map_err(|_| "Server has no mods folder".to_string())?;
+
+    let mut jars = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if !path.is_file() || !file_name.ends_with(".jar") {
+            continue;
+        }
+
+        jars.push(ModJar {
+            sha1: hash_file::<Sha1>(&path)?,
+            sha512: hash_file::<Sha512>(&path)?,
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            side: read_mod_jar(&path, false).environment,
+            path,
+            file_name,
+        });
+    }
+
+    jars.sort_by_key(|j| j.file_name.to_lowercase());
+
+    Ok(jars)
+}
+
+/// Download URLs of the jars Modrinth knows, keyed by sha1
+async fn modrinth_urls(hashes: Vec<String>) -> HashMap<String, String> {
+    // Offline: everything gets embedded
+    let versions = versions_by_hash(&http_client(), hashes)
+        .await
+        .unwrap_or_default();
+
+    versions
+        .into_iter()
+        .filter_map(|(sha1, version)| {
+            let url = version
+                .files
+                .iter()
+                .find(|f| f.hashes.get("sha1") == Some(&sha1))?
+                .url
+                .clone();
+
+            Some((sha1, url))
+        })
+        .collect()
+}
+
+fn loader_dependency(server: &ServerConfig) -> Result<(&'static str, String), String> {
+    let key = match server.loader {
+        LoaderType::Fabric => "fabric-loader",
+        LoaderType::Forge => "forge",
+        LoaderType::NeoForge => "neoforge",
+        _ => return Err("Only Fabric, Forge and NeoForge servers can be exported as a modpack".into()),
+    };
+
+    let build = server
+        .loader_build
+        .clone()
+        .ok_or("The loader build of this server is unknown")?;
+
+    Ok((key, build))
+}
+
+fn env(side: ModEnvironment) -> MrPackEnv {
+    let on = |needed: bool| if needed { "required" } else { "unsupported" }.to_string();
+
+    MrPackEnv {
+        client: on(side != ModEnvironment::Server),
+        server: on(side != ModEnvironment::Client),
+    }
+}
+
+fn override_dir(side: ModEnvironment) -> &'static str {
+    match side {
+        ModEnvironment::Both => "overrides",
+        ModEnvironment::Client => "client-overrides",
+        ModEnvironment::Server => "server-overrides",
+    }
+}
+
+fn add_file(zip: &mut ZipWriter<fs::File>, name: &str, source: &Path) -> Result<(), String> {
+    zip.start_file(name, SimpleFileOptions::default())
+        .map_err(|e| e.to_string())?;
+
+    let mut file = fs::File::open(source).map_err(|e| e.to_string())?;
+    std::io::copy(&mut file, zip).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn write_mrpack(path: &Path, index: &MrPackIndex, embedded: &[&ModJar]) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+
+    zip.start_file(INDEX_FILE, SimpleFileOptions::default())
+        .map_err(|e| e.to_string())?;
+    zip.write_all(serde_json::to_string_pretty(index).unwrap().as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    for jar in embedded {
+        let name = format!("{}/mods/{}", override_dir(jar.side), jar.file_name);
+        add_file(&mut zip, &name, &jar.path)?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn write_client_zip(path: &Path, jars: &[&ModJar]) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+
+    for jar in jars {
+        add_file(&mut zip, &format!("mods/{}", jar.file_name), &jar.path)?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn file_names<'a>(jars: impl Iterator<Item = &'a ModJar>) -> Vec<String> {
+    jars.map(|j| j.file_name.clone()).collect()
+}
+
+#[tauri::command]
+pub async fn export_server_modpack(
+    server_id: String,
+    dest_dir: Option<String>,
+) -> Result<ModpackExport, String> {
+    let server = find_server(&server_id)?;
+
+    let (loader_key, loader_build) = loader_dependency(&server)?;
+    let jars = scan_mods(&server)?;
+    let urls = modrinth_urls(jars.iter().map(|j| j.sha1.clone()).collect()).await;
+
+    let mut files = Vec::new();
+    let mut embedded = Vec::new();
+
+    for jar in &jars {
+        let Some(url) = urls.get(&jar.sha1) else {
+            embedded.push(jar);
+            continue;
+        };
+
+        files.push(MrPackFile {
+            path: format!("mods/{}", jar.file_name),
+            hashes: HashMap::from([
+                ("sha1".to_string(), jar.sha1.clone()),
+                ("sha512".to_string(), jar.sha512.clone()),
+            ]),
+            env: Some(env(jar.side)),
+            downloads: vec![url.clone()],
+            file_size: Some(jar.size),
+        });
+    }
+
+    let index = MrPackIndex {
+        format_version: 1,
+        game: "minecraft".into(),
+        version_id: Utc::now().format("%Y.%m.%d-%H%M").to_string(),
+        name: server.name.clone(),
+        summary: None,
+        files,
+        dependencies: HashMap::from([
+            ("minecraft".to_string(), server.version.clone()),
+            (loader_key.to_string(), loader_build),
+        ]),
+    };
+
+    let dir = dest_dir.map(PathBuf::from).unwrap_or_else(exports_dir);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let stem = format!("{}-{}", server_slug(&server.name), index.version_id);
+    let mrpack_path = dir.join(format!("{}.mrpack", stem));
+    let client_zip_path = dir.join(format!("{}-client-mods.zip", stem));
+
+    let client_jars: Vec<&ModJar> = jars.iter().filter(|j| j.side != ModEnvironment::Server).collect();
+
+    let written = write_mrpack(&mrpack_path, &index, &embedded)
+        .and_then(|_| write_client_zip(&client_zip_path, &client_jars));
+
+    if let Err(e) = written {
+        fs::remove_file(&mrpack_path).ok();
+        fs::remove_file(&client_zip_path).ok();
+        return Err(format!("Failed to write modpack: {}", e));
+    }
+
+    Ok(ModpackExport {
+        mrpack_path: mrpack_path.to_string_lossy().to_string(),
+        client_zip_path: client_zip_path.to_string_lossy().to_string(),
+        linked: file_names(jars.iter().filter(|j| urls.contains_key(&j.sha1))),
+        embedded: file_names(embedded.into_iter()),
+        server_only: file_names(jars.iter().filter(|j| j.side == ModEnvironment::Server)),
+    })
+}
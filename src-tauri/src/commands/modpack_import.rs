@@ -0,0 +1,319 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::commands::creation_jobs::{CreatePhase, CreationJob};
+use crate::commands::server_creation::{
+    create_server_from, CreateServerResult, LoaderType, ServerSource,
+};
+use crate::commands::server_import::default_ram_gb;
+use crate::state::app_state::AppState;
+use crate::utils::download::Checksum;
+use crate::utils::network::http_client;
+
+/// MODRINTH MODPACKS
+///
+/// A `.mrpack` is a zip holding `modrinth.index.json`, which names the game
+/// version, the loader and every file to download, plus `overrides/` and
+/// `server-overrides/` folders copied over the server afterwards.
+
+pub const INDEX_FILE: &str = "modrinth.index.json";
+
+// Applied in this order, so server-only files win
+const OVERRIDE_DIRS: &[&str] = &["overrides", "server-overrides"];
+
+// The only hosts the format allows downloads from
+const ALLOWED_HOSTS: &[&str] = &[
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrPackIndex {
+    pub format_version: u32,
+    pub game: String,
+    pub version_id: String,
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    pub files: Vec<MrPackFile>,
+    pub dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrPackFile {
+    pub path: String,
+    pub hashes: HashMap<String, String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<MrPackEnv>,
+
+    pub downloads: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MrPackEnv {
+    pub client: String,
+    pub server: String,
+}
+
+impl MrPackFile {
+    /// Files without an env are needed everywhere
+    fn server_side(&self) -> &str {
+        self.env.as_ref().map(|e| e.server.as_str()).unwrap_or("required")
+    }
+}
+
+/// A parsed pack and what it resolved to
+#[derive(Debug, Clone)]
+pub struct MrPack {
+    pub archive: PathBuf,
+    pub index: MrPackIndex,
+    pub version: String,
+    pub loader: LoaderType,
+    pub loader_build: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MrPackSummary {
+    pub name: String,
+    pub version_id: String,
+    pub summary: Option<String>,
+    pub version: String,
+    pub loader: LoaderType,
+    pub loader_build: Option<String>,
+    pub server_files: usize,
+    pub optional_files: Vec<String>, // server-optional files, not installed
+}
+
+/// Relative path that stays inside the server directory, None for anything else
+pub fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+
+    if path.as_os_str().is_empty()
+        || !path.components().all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    Some(path.to_path_buf())
+}
+
+fn allowed_url(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .filter(|u| u.scheme() == "https")
+        .and_then(|u| u.host_str().map(|h| ALLOWED_HOSTS.contains(&h)))
+        .unwrap_or(false)
+}
+
+/// Maps the pack's dependencies to a loader and, if pinned, its build
+fn resolve_loader(dependencies: &HashMap<String, String>) -> Result<(LoaderType, Option<String>), String> {
+    if let Some(build) = dependencies.get("fabric-loader") {
+        return Ok((LoaderType::Fabric, Some(build.clone())));
+    }
+
+    if let Some(build) = dependencies.get("forge") {
+        return Ok((LoaderType::Forge, Some(build.clone())));
+    }
+
+    if let Some(build) = dependencies.get("neoforge") {
+        return Ok((LoaderType::NeoForge, Some(build.clone())));
+    }
+
+    if dependencies.contains_key("quilt-loader") {
+        return Err("Quilt modpacks aren't supported".into());
+    }
+
+    Ok((LoaderType::Vanilla, None))
+}
+
+pub fn read_mrpack(archive: &Path) -> Result<MrPack, String> {
+    let file = fs::File::open(archive).map_err(|e| format!("Failed to open modpack: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Not a modpack archive: {}", e))?;
+
+    let index: MrPackIndex = {
+        let entry = zip
+            .by_name(INDEX_FILE)
+            .map_err(|_| format!("{} is missing from the modpack", INDEX_FILE))?;
+
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid {}: {}", INDEX_FILE, e))?
+    };
+
+    if index.format_version != 1 || index.game != "minecraft" {
+        return Err(format!(
+            "Unsupported modpack format {} for {}",
+            index.format_version, index.game
+        ));
+    }
+
+    let version = index
+        .dependencies
+        .get("minecraft")
+        .cloned()
+        .ok_or("Modpack doesn't name a Minecraft version")?;
+
+    let (loader, loader_build) = resolve_loader(&index.dependencies)?;
+
+    Ok(MrPack {
+        archive: archive.to_path_buf(),
+        index,
+        version,
+        loader,
+        loader_build,
+    })
+}
+
+/// Copies the contents of each of `dirs` in the archive into the server, in order
+pub fn extract_overrides(archive: &Path, dirs: &[&str], server_path: &Path) -> Result<(), String> {
+    let file = fs::File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|e| e.to_string())?;
+
+    for dir in dirs {
+        let prefix = format!("{}/", dir);
+
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+
+            let Some(rel) = entry
+                .name()
+                .strip_prefix(&prefix)
+                .and_then(safe_relative_path)
+            else {
+                continue;
+            };
+
+            let out = server_path.join(rel);
+
+            if entry.is_dir() {
+                fs::create_dir_all(&out).map_err(|e| e.to_string())?;
+                continue;
+            }
+
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+
+            let mut outfile = fs::File::create(&out).map_err(|e| e.to_string())?;
+            io::copy(&mut entry, &mut outfile).map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Downloads the pack's server files and applies its overrides.
+/// Files that can't be fetched from any of their sources are returned
+/// instead of failing the whole server.
+pub async fn install_mrpack_files(
+    pack: &MrPack,
+    server_path: &Path,
+    job: &CreationJob,
+) -> Result<Vec<String>, String> {
+    let client = http_client();
+    let mut failed = Vec::new();
+
+    for file in pack.index.files.iter().filter(|f| f.server_side() == "required") {
+        job.check_cancelled()?;
+
+        // Refuse to write outside the server or to keep files we can't verify
+        let (Some(rel), Some(sha1)) = (safe_relative_path(&file.path), file.hashes.get("sha1"))
+        else {
+            failed.push(file.path.clone());
+            continue;
+        };
+
+        let dest = server_path.join(rel);
+        let checksum = Checksum::Sha1(sha1.clone());
+        let mut fetched = false;
+
+        for url in file.downloads.iter().filter(|u| allowed_url(u)) {
+            match job.download(&client, url, &dest, Some(&checksum)).await {
+                Ok(_) => {
+                    fetched = true;
+                    break;
+                }
+                Err(e) => {
+                    job.check_cancelled()?;
+                    job.log(format!("Failed to download {} from {}: {}", file.path, url, e));
+                }
+            }
+        }
+
+        if !fetched {
+            failed.push(file.path.clone());
+        }
+    }
+
+    job.check_cancelled()?;
+    job.set_phase(CreatePhase::WritingFiles);
+    extract_overrides(&pack.archive, OVERRIDE_DIRS, server_path)?;
+
+    Ok(failed)
+}
+
+#[tauri::command]
+pub fn inspect_mrpack(path: String) -> Result<MrPackSummary, String> {
+    let pack = read_mrpack(Path::new(&path))?;
+
+    let files_with = |side: &str| {
+        pack.index
+            .files
+            .iter()
+            .filter(|f| f.server_side() == side)
+            .map(|f| f.path.clone())
+            .collect::<Vec<_>>()
+    };
+
+    Ok(MrPackSummary {
+        name: pack.index.name.clone(),
+        version_id: pack.index.version_id.clone(),
+        summary: pack.index.summary.clone(),
+        version: pack.version.clone(),
+        loader: pack.loader.clone(),
+        loader_build: pack.loader_build.clone(),
+        server_files: files_with("required").len(),
+        optional_files: files_with("optional"),
+    })
+}
+
+#[tauri::command]
+pub async fn import_mrpack(
+    path: String,
+    name: Option<String>,
+    ram_gb: Option<u8>,
+    job_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CreateServerResult, String> {
+    let pack = read_mrpack(Path::new(&path))?;
+
+    let name = name.unwrap_or_else(|| pack.index.name.clone());
+    let ram_gb = ram_gb.unwrap_or_else(|| default_ram_gb(&pack.loader));
+
+    create_server_from(
+        name,
+        pack.version.clone(),
+        pack.loader.clone(),
+        ram_gb,
+        ServerSource::Modrinth(pack),
+        job_id,
+        &state,
+    )
+    .await
+}
@@ -0,0 +1,516 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use sha1::Sha1;
+
+use crate::commands::artifact_cache::cached_download;
+use crate::commands::mod_profiles::jar_location;
+use crate::commands::mod_sources::{InstalledFile, ModProvider, ModSources};
+use crate::commands::server_creation::LoaderType;
+use crate::commands::server_management::{ensure_stopped, find_server, ServerConfig};
+use crate::state::app_state::AppState;
+use crate::utils::download::{hash_file, Checksum, DownloadEvents};
+use crate::utils::network::{endpoint, http_client, Endpoint};
+
+/// MODRINTH
+///
+/// Search, project and version lookups against Modrinth's v2 API (its base
+/// URL is the `Modrinth` endpoint, so it can point at a local stub), and
+/// installing a mod, or a plugin on Paper, together with every required
+/// dependency. Installed files are verified against Modrinth's sha1 and
+/// recorded in `ModSources`.
+
+const MODS_FOLDER: &str = "mods";
+pub const PLUGINS_FOLDER: &str = "plugins";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthFile {
+    pub url: String,
+    pub filename: String,
+    pub primary: bool,
+    pub size: u64,
+    pub hashes: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthDependency {
+    pub version_id: Option<String>,
+    pub project_id: Option<String>,
+    pub dependency_type: String, // required, optional, incompatible or embedded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthVersion {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub version_number: String,
+    pub version_type: String, // release, beta or alpha
+    pub date_published: String,
+
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+
+    #[serde(default)]
+    pub loaders: Vec<String>,
+
+    pub files: Vec<ModrinthFile>,
+
+    #[serde(default)]
+    pub dependencies: Vec<ModrinthDependency>,
+}
+
+impl ModrinthVersion {
+    pub fn primary_file(&self) -> Option<&ModrinthFile> {
+        self.files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| self.files.first())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthHit {
+    pub project_id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub downloads: u64,
+
+    #[serde(default)]
+    pub icon_url: Option<String>,
+
+    pub client_side: String,
+    pub server_side: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthSearch {
+    pub hits: Vec<ModrinthHit>,
+    pub offset: u32,
+    pub limit: u32,
+    pub total_hits: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthProject {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub body: String,
+
+    #[serde(default)]
+    pub icon_url: Option<String>,
+
+    pub client_side: String,
+    pub server_side: String,
+    pub downloads: u64,
+
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModInstallResult {
+    pub installed: Vec<InstalledFile>,
+    pub already_installed: Vec<String>, // dependencies that were there already, by project id
+    pub missing_dependencies: Vec<String>, // required but with nothing to install, to get by hand
+}
+
+/// API
+
+fn api_url(path: &str, params: &[(&str, String)]) -> Result<Url, String> {
+    let base = format!("{}/v2{}", endpoint(Endpoint::Modrinth), path);
+    Url::parse_with_params(&base, params).map_err(|e| e.to_string())
+}
+
+async fn modrinth_get<T: DeserializeOwned>(client: &Client, url: Url) -> Result<T, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Modrinth: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Modrinth returned {}", response.status()));
+    }
+
+    response.json().await.map_err(|e| e.to_string())
+}
+
+/// Folder a server keeps its Modrinth content in and the loaders it can run,
+/// Paper loads Spigot and Bukkit plugins too
+pub fn content_target(loader: &LoaderType) -> Result<(&'static str, &'static [&'static str]), String> {
+    match loader {
+        LoaderType::Fabric => Ok((MODS_FOLDER, &["fabric"])),
+        LoaderType::Forge => Ok((MODS_FOLDER, &["forge"])),
+        LoaderType::NeoForge => Ok((MODS_FOLDER, &["neoforge"])),
+        LoaderType::Paper => Ok((PLUGINS_FOLDER, &["paper", "spigot", "bukkit"])),
+        _ => Err("This server can't load mods or plugins".into()),
+    }
+}
+
+/// Versions of a project that run on `version` and any of `loaders`, newest first
+pub async fn compatible_versions(
+    client: &Client,
+    project_id: &str,
+    version: &str,
+    loaders: &[&str],
+) -> Result<Vec<ModrinthVersion>, String> {
+    let url = api_url(
+        &format!("/project/{}/version", project_id),
+        &[
+            ("game_versions", json!([version]).to_string()),
+            ("loaders", json!(loaders).to_string()),
+        ],
+    )?;
+
+    modrinth_get(client, url).await
+}
+
+/// Versions of the files with the given sha1 hashes, keyed by hash. Unknown files are left out.
+pub async fn versions_by_hash(
+    client: &Client,
+    hashes: Vec<String>,
+) -> Result<HashMap<String, ModrinthVersion>, String> {
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let response = client
+        .post(api_url("/version_files", &[])?)
+        .json(&json!({ "hashes": hashes, "algorithm": "sha1" }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Modrinth: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Modrinth returned {}", response.status()));
+    }
+
+    response.json().await.map_err(|e| e.to_string())
+}
+
+/// Projects already in `folder`, installed by Cubely or recognised by hash
+async fn installed_projects(client: &Client, server_path: &Path, folder: &str) -> HashSet<String> {
+    let mut projects: HashSet<String> = ModSources::load(server_path)
+        .files
+        .into_iter()
+        .map(|f| f.project_id)
+        .collect();
+
+    let hashes: Vec<String> = fs::read_dir(server_path.join(folder))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "jar"))
+        .filter_map(|p| hash_file::<Sha1>(&p).ok())
+        .collect();
+
+    // Offline just means fewer jars are recognised
+    if let Ok(versions) = versions_by_hash(client, hashes).await {
+        projects.extend(versions.into_values().map(|v| v.project_id));
+    }
+
+    projects
+}
+
+/// The requested version and every required dependency not installed yet,
+/// the requested one first, plus dependencies with no build for this server
+async fn resolve_install(
+    client: &Client,
+    server: &ServerConfig,
+    project_id: &str,
+    version_id: Option<String>,
+    installed: &HashSet<String>,
+) -> Result<(Vec<ModrinthVersion>, Vec<String>, Vec<String>), String> {
+    let (_, loaders) = content_target(&server.loader)?;
+
+    let mut plan: Vec<ModrinthVersion> = Vec::new();
+    let mut already_installed = Vec::new();
+    let mut missing = Vec::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([(Some(project_id.to_string()), version_id)]);
+
+    while let Some((project, version)) = queue.pop_front() {
+        let root = plan.is_empty() && already_installed.is_empty();
+
+        let version = match (version, project) {
+            (Some(version_id), _) => {
+                modrinth_get::<ModrinthVersion>(
+                    client,
+                    api_url(&format!("/version/{}", version_id), &[])?,
+                )
+                .await?
+            }
+            (None, Some(project)) => {
+                if seen.contains(&project) {
+                    continue;
+                }
+
+                let found = compatible_versions(client, &project, &server.version, loaders)
+                    .await?
+                    .into_iter()
+                    .next();
+
+                match found {
+                    Some(version) => version,
+                    // A dependency without a build is reported, the rest still installs
+                    None if !root => {
+                        seen.insert(project.clone());
+                        missing.push(project);
+                        continue;
+                    }
+                    None => {
+                        return Err(format!(
+                            "{} has no version for {} {}",
+                            project, loaders[0], server.version
+                        ))
+                    }
+                }
+            }
+            (None, None) => continue,
+        };
+
+        if !seen.insert(version.project_id.clone()) {
+            continue;
+        }
+
+        if installed.contains(&version.project_id) {
+            if root {
+                return Err("This project is already installed".into());
+            }
+
+            already_installed.push(version.project_id.clone());
+            continue;
+        }
+
+        for dependency in &version.dependencies {
+            match dependency.dependency_type.as_str() {
+                "required" => {
+                    queue.push_back((dependency.project_id.clone(), dependency.version_id.clone()))
+                }
+                "incompatible" => {
+                    let clash = dependency.project_id.as_ref().is_some_and(|p| {
+                        installed.contains(p) || plan.iter().any(|v| &v.project_id == p)
+                    });
+
+                    if clash {
+                        return Err(format!(
+                            "{} is incompatible with {}, which is installed",
+                            version.name,
+                            dependency.project_id.as_deref().unwrap_or_default()
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        plan.push(version);
+    }
+
+    Ok((plan, already_installed, missing))
+}
+
+/// Commands
+
+#[tauri::command]
+pub async fn search_modrinth(
+    server_id: String,
+    query: String,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> Result<ModrinthSearch, String> {
+    let server = find_server(&server_id)?;
+    let (folder, loaders) = content_target(&server.loader)?;
+
+    let categories: Vec<String> = loaders.iter().map(|l| format!("categories:{}", l)).collect();
+
+    // Facets are ANDed lists of ORed filters. Plugins rarely fill in their
+    // sides, so only mods are filtered on running server side.
+    let facets = match folder {
+        MODS_FOLDER => json!([
+            ["project_type:mod"],
+            categories,
+            [format!("versions:{}", server.version)],
+            ["server_side:required", "server_side:optional"],
+        ]),
+        _ => json!([
+            ["project_type:plugin"],
+            categories,
+            [format!("versions:{}", server.version)],
+        ]),
+    };
+
+    let url = api_url(
+        "/search",
+        &[
+            ("query", query),
+            ("facets", facets.to_string()),
+            ("offset", offset.unwrap_or(0).to_string()),
+            ("limit", limit.unwrap_or(20).min(100).to_string()),
+        ],
+    )?;
+
+    modrinth_get(&http_client(), url).await
+}
+
+#[tauri::command]
+pub async fn get_modrinth_project(project_id: String) -> Result<ModrinthProject, String> {
+    let url = api_url(&format!("/project/{}", project_id), &[])?;
+    modrinth_get(&http_client(), url).await
+}
+
+#[tauri::command]
+pub async fn get_modrinth_versions(
+    server_id: String,
+    project_id: String,
+) -> Result<Vec<ModrinthVersion>, String> {
+    let server = find_server(&server_id)?;
+    let (_, loaders) = content_target(&server.loader)?;
+
+    compatible_versions(&http_client(), &project_id, &server.version, loaders).await
+}
+
+#[tauri::command]
+pub async fn install_modrinth_mod(
+    server_id: String,
+    project_id: String,
+    version_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModInstallResult, String> {
+    let app = {
+        let guard = state.app_handle.lock().unwrap();
+        guard.clone().ok_or("App handle not initialized")?
+    };
+
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+    let (folder, _) = content_target(&server.loader)?;
+    let client = http_client();
+
+    let installed = installed_projects(&client, &server_path, folder).await;
+    let (plan, already_installed, missing_dependencies) =
+        resolve_install(&client, &server, &project_id, version_id, &installed).await?;
+
+    let mods_dir = server_path.join(folder);
+    fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?;
+
+    let mut sources = ModSources::load(&server_path);
+    let mut written: Vec<InstalledFile> = Vec::new();
+    let mut created: Vec<PathBuf> = Vec::new(); // only these are removed on rollback
+
+    for (i, version) in plan.iter().enumerate() {
+        let result = async {
+            let file = version
+                .primary_file()
+                .ok_or(format!("{} has no files", version.name))?;
+            let sha1 = file
+                .hashes
+                .get("sha1")
+                .ok_or(format!("{} has no checksum", file.filename))?;
+
+            // Names come from the API, keep them inside the folder
+            if file.filename.contains(['/', '\\']) || file.filename.starts_with('.') {
+                return Err(format!("Invalid file name {}", file.filename));
+            }
+
+            // Never overwrite a jar someone put there, the rollback would lose it
+            let dest = mods_dir.join(&file.filename);
+            if dest.exists() {
+                if hash_file::<Sha1>(&dest).ok().as_ref() != Some(sha1) {
+                    return Err(format!(
+                        "{} already exists in {}, remove it first",
+                        file.filename, folder
+                    ));
+                }
+            } else {
+                let progress = DownloadEvents::new(app.clone(), file.filename.clone());
+                cached_download(
+                    &client,
+                    &file.url,
+                    &dest,
+                    Some(&Checksum::Sha1(sha1.clone())),
+                    &progress,
+                )
+                .await?;
+
+                created.push(dest);
+            }
+
+            Ok(InstalledFile {
+                folder: folder.to_string(),
+                file_name: file.filename.clone(),
+                provider: ModProvider::Modrinth,
+                project_id: version.project_id.clone(),
+                version_id: version.id.clone(),
+                version_number: version.version_number.clone(),
+                sha1: sha1.clone(),
+                installed_at: Utc::now().timestamp(),
+                dependency: i > 0,
+            })
+        }
+        .await;
+
+        match result {
+            Ok(file) => written.push(file),
+            Err(e) => {
+                // All or nothing, a mod without its dependencies won't start
+                for path in &created {
+                    fs::remove_file(path).ok();
+                }
+
+                return Err(format!("Failed to install {}: {}", version.name, e));
+            }
+        }
+    }
+
+    for file in &written {
+        sources.record(file.clone());
+    }
+    sources.save(&server_path)?;
+
+    Ok(ModInstallResult {
+        installed: written,
+        already_installed,
+        missing_dependencies,
+    })
+}
+
+#[tauri::command]
+pub fn remove_mod(
+    server_id: String,
+    file_name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    ensure_stopped(&server_id, &state, "Stop the server before removing mods")?;
+
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+    let (folder, _) = content_target(&server.loader)?;
+
+    if file_name.contains(['/', '\\']) || !file_name.ends_with(".jar") {
+        return Err("Invalid mod file".into());
+    }
+
+    // Disabled jars are renamed or moved aside, remove them wherever they are
+    let path = jar_location(&server_path.join(folder), &file_name)
+        .ok_or(format!("{} not found", file_name))?;
+
+    fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", file_name, e))?;
+
+    let mut sources = ModSources::load(&server_path);
+    sources.forget(folder, &file_name);
+    sources.save(&server_path)
+}
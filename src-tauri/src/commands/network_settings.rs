@@ -0,0 +1,39 @@
+use serde::Serialize;
+
+use crate::utils::network::{network_settings, save_network_settings, Endpoint, NetworkSettings};
+
+/// Network Settings Commands
+
+#[derive(Debug, Serialize)]
+pub struct EndpointInfo {
+    pub endpoint: Endpoint,
+    pub default_url: String,
+    pub mirror: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetworkSettingsInfo {
+    pub endpoints: Vec<EndpointInfo>,
+    pub settings: NetworkSettings,
+}
+
+#[tauri::command]
+pub fn get_network_settings() -> NetworkSettingsInfo {
+    let settings = network_settings();
+
+    let endpoints = Endpoint::ALL
+        .iter()
+        .map(|&endpoint| EndpointInfo {
+            endpoint,
+            default_url: endpoint.default_url().to_string(),
+            mirror: settings.mirrors.get(&endpoint).cloned(),
+        })
+        .collect();
+
+    NetworkSettingsInfo { endpoints, settings }
+}
+
+#[tauri::command]
+pub fn update_network_settings(settings: NetworkSettings) -> Result<(), String> {
+    save_network_settings(settings)
+}
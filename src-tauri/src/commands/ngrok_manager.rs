@@ -3,6 +3,9 @@ use std::{fs, path::{Path, PathBuf}, process::Command};
 use reqwest::Client;
 use zip::ZipArchive;
 
+use crate::utils::download::{download_file, DownloadObserver};
+use crate::utils::network::{endpoint, http_client, Endpoint};
+
 /// Installing Ngrok
 
 fn ngrok_binary_name() -> &'static str {
@@ -25,34 +28,27 @@ fn zip_path(base: &PathBuf) -> PathBuf {
     base.join("ngrok.zip")
 }
 
-fn ngrok_download_url() -> &'static str {
+fn ngrok_download_url() -> String {
     #[cfg(target_os = "windows")]
-    { "https://bin.equinox.io/c/bNyj1mQVY4c/ngrok-v3-stable-windows-amd64.zip" }
+    let file = "ngrok-v3-stable-windows-amd64.zip";
 
     #[cfg(target_os = "macos")]
-    { "https://bin.equinox.io/c/bNyj1mQVY4c/ngrok-v3-stable-darwin-amd64.zip" }
+    let file = "ngrok-v3-stable-darwin-amd64.zip";
 
     #[cfg(target_os = "linux")]
-    { "https://bin.equinox.io/c/bNyj1mQVY4c/ngrok-v3-stable-linux-amd64.zip" }
+    let file = "ngrok-v3-stable-linux-amd64.zip";
+
+    format!("{}/c/bNyj1mQVY4c/{}", endpoint(Endpoint::Ngrok), file)
 }
 
-pub async fn install_ngrok(base: &PathBuf) -> Result<(), String> {
+pub async fn install_ngrok(base: &PathBuf, observer: &dyn DownloadObserver) -> Result<(), String> {
     fs::create_dir_all(base).map_err(|e| e.to_string())?;
 
     let marker = installing_marker(base);
     fs::write(&marker, b"").ok();
 
-    let bytes = Client::new()
-        .get(ngrok_download_url())
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .bytes()
-        .await
-        .map_err(|e| e.to_string())?;
-
     let zip = zip_path(base);
-    fs::write(&zip, &bytes).map_err(|e| e.to_string())?;
+    download_file(&http_client(), &ngrok_download_url(), &zip, None, observer).await?;
 
     let file = fs::File::open(&zip).map_err(|e| e.to_string())?;
     let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
@@ -122,12 +118,21 @@ pub fn ngrok_installed(base: &PathBuf) -> bool {
 }
 
 /// Start Ngrok
+use crate::commands::server_management::TunnelProtocol;
 use serde_json::Value;
 use std::process::{Child, Stdio};
 use std::thread::sleep;
 use std::time::Duration;
 
-pub async fn start_ngrok(port: u16, base: &PathBuf) -> Result<(Child, String), String> {
+pub async fn start_ngrok(
+    port: u16,
+    protocol: TunnelProtocol,
+    base: &PathBuf,
+) -> Result<(Child, String), String> {
+    if protocol == TunnelProtocol::Udp {
+        return Err("Ngrok cannot forward UDP traffic. Use the Playit tunnel for Bedrock servers.".into());
+    }
+
     let bin = ngrok_binary(base);
 
     let child = Command::new(bin)
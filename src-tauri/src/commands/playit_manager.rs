@@ -9,6 +9,9 @@ use serde_json::Value;
 use tauri::{AppHandle, Emitter};
 use zip::ZipArchive;
 
+use crate::utils::download::{download_file, DownloadObserver};
+use crate::utils::network::{endpoint, http_client, Endpoint};
+
 /// Playit Binary Resolution
 fn playit_binary_name() -> &'static str {
     #[cfg(target_os = "windows")]
@@ -34,53 +37,44 @@ fn zip_path(base: &PathBuf) -> PathBuf {
 
 /// Download URLs (official + existing)
 #[cfg(windows)]
-fn playit_download_url() -> &'static str {
-    "https://github.com/playit-cloud/playit-agent/releases/latest/download/playit-windows-x86_64.exe"
+fn playit_download_url() -> String {
+    format!("{}/latest/download/playit-windows-x86_64.exe", endpoint(Endpoint::PlayitDownloads))
 }
 
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-fn playit_download_url() -> &'static str {
-    "https://github.com/playit-cloud/playit-agent/releases/latest/download/playit-linux-amd64"
+fn playit_download_url() -> String {
+    format!("{}/latest/download/playit-linux-amd64", endpoint(Endpoint::PlayitDownloads))
 }
 
 #[cfg(all(target_os = "linux", target_arch = "aarch64"))]
-fn playit_download_url() -> &'static str {
-    "https://github.com/playit-cloud/playit-agent/releases/latest/download/playit-linux-aarch64"
+fn playit_download_url() -> String {
+    format!("{}/latest/download/playit-linux-aarch64", endpoint(Endpoint::PlayitDownloads))
 }
 
 
 /// Install Playit (transactional)
 #[cfg(target_os = "macos")]
-pub async fn install_playit(_: &PathBuf) -> Result<(), String> {
+pub async fn install_playit(_: &PathBuf, _: &dyn DownloadObserver) -> Result<(), String> {
     Err("Playit tunnel is not supported on macOS. Consider changing the provider or turn off Tunnel option and try again.".into())
 }
 
 #[cfg(not(target_os = "macos"))]
-pub async fn install_playit(base: &PathBuf) -> Result<(), String> {
+pub async fn install_playit(base: &PathBuf, observer: &dyn DownloadObserver) -> Result<(), String> {
     fs::create_dir_all(base).map_err(|e| e.to_string())?;
 
     let marker = installing_marker(base);
     fs::write(&marker, b"").ok();
 
-    let resp = Client::new()
-        .get(playit_download_url())
-        .send()
+    let bin = playit_binary(base);
+    let size = download_file(&http_client(), &playit_download_url(), &bin, None, observer)
         .await
-        .map_err(|e| e.to_string())?;
-
-    if !resp.status().is_success() {
-        return Err(format!("Failed to download playit: {}", resp.status()));
-    }
-
-    let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+        .map_err(|e| format!("Failed to download playit: {}", e))?;
 
-    if bytes.len() < 500_000 {
+    if size < 500_000 {
+        fs::remove_file(&bin).ok();
         return Err("Downloaded playit binary is suspiciously small".into());
     }
 
-    let bin = playit_binary(base);
-    fs::write(&bin, &bytes).map_err(|e| e.to_string())?;
-
     #[cfg(not(target_os = "windows"))]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -179,23 +173,88 @@ pub fn start_playit(base: &PathBuf) -> Result<Child, String> {
         .map_err(|e| e.to_string())
 }
 
-use playit_api_client::PlayitApi;
+use crate::commands::server_management::TunnelProtocol;
+use playit_api_client::api::{ApiResult, PlayitApiClient, PlayitHttpClient, PortType};
+use reqwest::{header::AUTHORIZATION, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use std::panic::Location;
 use std::time::Duration;
 
-pub async fn get_playit_public_url(app: AppHandle) -> Result<String, String> {
+/// Sends Playit API calls through the shared client, the one the library
+/// builds on its own ignores the proxy settings
+struct PlayitHttp {
+    client: Client,
+    auth_header: String,
+}
+
+impl PlayitHttpClient for PlayitHttp {
+    type Error = String;
+
+    async fn call<Req: Serialize + Send, Res: DeserializeOwned, Err: DeserializeOwned>(
+        &self,
+        _caller: &'static Location<'static>,
+        path: &str,
+        req: Req,
+    ) -> Result<ApiResult<Res, Err>, String> {
+        let response = self
+            .client
+            .post(format!("{}{}", endpoint(Endpoint::PlayitApi), path))
+            .header(AUTHORIZATION, &self.auth_header)
+            .json(&req)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err("Too many requests".into());
+        }
+
+        response.json().await.map_err(|e| e.to_string())
+    }
+}
+
+/// Resolves the public address of the Playit tunnel forwarding `port` over `protocol`.
+/// Falls back to any tunnel of the right protocol when none targets the port.
+pub async fn get_playit_public_url(
+    app: AppHandle,
+    port: u16,
+    protocol: TunnelProtocol,
+) -> Result<String, String> {
     let secret = read_playit_secret()?;
-    let api = PlayitApi::create("https://api.playit.gg".to_string(), Some(secret));
+    let api = PlayitApiClient::new(PlayitHttp {
+        client: http_client(),
+        auth_header: format!("Agent-Key {}", secret.trim()),
+    });
 
     let data = api
         .agents_rundata()
         .await
         .map_err(|e| format!("Playit API error: {}", e))?;
 
-    data.tunnels
+    let carries_protocol = |proto: &PortType| match protocol {
+        TunnelProtocol::Tcp => matches!(proto, PortType::Tcp | PortType::Both),
+        TunnelProtocol::Udp => matches!(proto, PortType::Udp | PortType::Both),
+    };
+
+    let tunnels: Vec<_> = data
+        .tunnels
         .into_iter()
-        .next()
-        .map(|t| t.assigned_domain)
-        .ok_or("No Playit tunnel found".into())
+        .filter(|t| t.disabled.is_none() && carries_protocol(&t.proto))
+        .collect();
+
+    tunnels
+        .iter()
+        .find(|t| t.local_port == port)
+        .or(tunnels.first())
+        .map(|t| match protocol {
+            // Java resolves the port via SRV records, Bedrock clients need it spelled out
+            TunnelProtocol::Tcp => t.assigned_domain.clone(),
+            TunnelProtocol::Udp => format!("{}:{}", t.assigned_domain, t.port.from),
+        })
+        .ok_or(match protocol {
+            TunnelProtocol::Tcp => "No Playit TCP tunnel found".into(),
+            TunnelProtocol::Udp => "No Playit UDP tunnel found. Create a UDP tunnel for the Bedrock port in the Playit dashboard.".into(),
+        })
 }
 
 fn playit_config_path() -> Option<PathBuf> {
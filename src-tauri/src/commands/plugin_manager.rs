@@ -0,0 +1,754 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::Sha256;
+use zip::ZipArchive;
+
+use crate::commands::artifact_cache::cached_download;
+use crate::commands::mod_checker::{
+    compare_versions, IssueKind, IssueSeverity, ModCheckReport, ModIssue,
+};
+use crate::commands::mod_profiles::{jar_location, jar_states};
+use crate::commands::mod_sources::{InstalledFile, ModProvider, ModSources};
+use crate::commands::modrinth_browser::{ModInstallResult, PLUGINS_FOLDER};
+use crate::commands::server_creation::LoaderType;
+use crate::commands::server_management::{find_server, ServerConfig};
+use crate::state::app_state::AppState;
+use crate::utils::download::{hash_file, Checksum, DownloadEvents};
+use crate::utils::network::{endpoint, http_client, Endpoint};
+
+/// PAPER PLUGINS
+///
+/// Reads `paper-plugin.yml` (or `plugin.yml`) from each jar in `plugins/`,
+/// enabled or not, and checks the enabled ones for missing dependencies,
+/// duplicate names and an `api-version` newer than the server. Plugins are
+/// toggled like mods, see `mod_profiles`. Plugins can be installed from
+/// Hangar, whose base URL is the `Hangar` endpoint, or from Modrinth
+/// through `modrinth_browser`.
+///
+/// Only the handful of keys read here are parsed, with a small reader for
+/// the YAML subset plugin descriptions are written in.
+
+const HANGAR_PLATFORM: &str = "PAPER";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    pub file_name: String,
+    pub enabled: bool,
+    pub size: u64,
+    pub paper_plugin: bool, // described by paper-plugin.yml
+    pub name: String,       // the file name when the jar has no description
+    pub version: Option<String>,
+    pub main: Option<String>,
+    pub api_version: Option<String>,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    pub depends: Vec<String>,
+    pub soft_depends: Vec<String>,
+    pub provides: Vec<String>, // other plugin names this one stands in for
+    pub has_metadata: bool,
+}
+
+impl PluginInfo {
+    fn unknown(file_name: &str, enabled: bool, size: u64) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            enabled,
+            size,
+            paper_plugin: false,
+            name: file_name.to_string(),
+            version: None,
+            main: None,
+            api_version: None,
+            description: None,
+            authors: Vec::new(),
+            depends: Vec::new(),
+            soft_depends: Vec::new(),
+            provides: Vec::new(),
+            has_metadata: false,
+        }
+    }
+}
+
+/// YAML
+///
+/// Top-level `key: value` pairs, each with the indented lines under it.
+/// Enough for plugin descriptions, which are flat apart from lists and
+/// paper-plugin.yml's `dependencies`.
+
+struct YamlEntry<'a> {
+    key: String,
+    value: &'a str,
+    block: Vec<&'a str>,
+}
+
+fn yaml_entries(text: &str) -> Vec<YamlEntry<'_>> {
+    let mut entries: Vec<YamlEntry> = Vec::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let top_level = !line.starts_with([' ', '\t']) && !trimmed.starts_with('-');
+
+        match (top_level, line.split_once(':')) {
+            (true, Some((key, value))) => entries.push(YamlEntry {
+                key: key.trim().to_string(),
+                value: value.trim(),
+                block: Vec::new(),
+            }),
+            _ => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.block.push(line);
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+/// A plain or quoted scalar, without a trailing comment
+fn yaml_scalar(value: &str) -> Option<String> {
+    let value = value.trim();
+
+    let value = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+        _ => value.split(" #").next().unwrap_or_default().trim(),
+    };
+
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// `[a, b]`, a block of `- a` lines, or a single scalar
+fn yaml_list(entry: &YamlEntry) -> Vec<String> {
+    if let Some(inner) = entry.value.strip_prefix('[') {
+        return inner
+            .trim_end_matches(']')
+            .split(',')
+            .filter_map(yaml_scalar)
+            .collect();
+    }
+
+    if !entry.value.is_empty() {
+        return yaml_scalar(entry.value).into_iter().collect();
+    }
+
+    entry
+        .block
+        .iter()
+        .filter_map(|line| line.trim().strip_prefix('-'))
+        .filter_map(yaml_scalar)
+        .collect()
+}
+
+/// A scalar, folding `>` and `|` blocks into one line
+fn yaml_text(entry: &YamlEntry) -> Option<String> {
+    if entry.value.starts_with(['>', '|']) {
+        let text: Vec<&str> = entry.block.iter().map(|l| l.trim()).collect();
+        return (!text.is_empty()).then(|| text.join(" "));
+    }
+
+    yaml_scalar(entry.value)
+}
+
+/// paper-plugin.yml's `dependencies.server`, each name with whether it is required
+fn paper_dependencies(block: &[&str]) -> Vec<(String, bool)> {
+    let mut dependencies: Vec<(String, bool)> = Vec::new();
+    let mut server_indent: Option<usize> = None;
+    let mut entry_indent: Option<usize> = None;
+
+    for line in block {
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+
+        if trimmed.starts_with('#') {
+            continue;
+        }
+
+        match server_indent {
+            Some(server) if indent > server => {
+                let Some((key, value)) = trimmed.split_once(':') else {
+                    continue;
+                };
+
+                let entry = *entry_indent.get_or_insert(indent);
+
+                if indent == entry {
+                    if let Some(name) = yaml_scalar(key) {
+                        // Required unless it says otherwise
+                        dependencies.push((name, true));
+                    }
+                } else if key.trim() == "required" {
+                    if let Some(last) = dependencies.last_mut() {
+                        last.1 = yaml_scalar(value).as_deref() != Some("false");
+                    }
+                }
+            }
+            _ => {
+                // Sections other than `server` run at bootstrap and don't count here
+                server_indent = (trimmed == "server:").then_some(indent);
+                entry_indent = None;
+            }
+        }
+    }
+
+    dependencies
+}
+
+fn parse_description(info: &mut PluginInfo, text: &str, paper_plugin: bool) {
+    info.has_metadata = true;
+    info.paper_plugin = paper_plugin;
+
+    for entry in yaml_entries(text) {
+        match entry.key.as_str() {
+            "name" => {
+                if let Some(name) = yaml_scalar(entry.value) {
+                    info.name = name;
+                }
+            }
+            "version" => info.version = yaml_scalar(entry.value),
+            "main" => info.main = yaml_scalar(entry.value),
+            "api-version" => info.api_version = yaml_scalar(entry.value),
+            "description" => info.description = yaml_text(&entry),
+            "author" => info.authors.extend(yaml_scalar(entry.value)),
+            "authors" => info.authors.extend(yaml_list(&entry)),
+            "depend" => info.depends.extend(yaml_list(&entry)),
+            "softdepend" => info.soft_depends.extend(yaml_list(&entry)),
+            "provides" => info.provides.extend(yaml_list(&entry)),
+            "dependencies" if paper_plugin => {
+                for (name, required) in paper_dependencies(&entry.block) {
+                    match required {
+                        true => info.depends.push(name),
+                        false => info.soft_depends.push(name),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_description(path: &Path) -> Option<(String, bool)> {
+    let file = fs::File::open(path).ok()?;
+    let mut jar = ZipArchive::new(file).ok()?;
+
+    // Paper prefers its own description when a jar ships both
+    for (name, paper_plugin) in [("paper-plugin.yml", true), ("plugin.yml", false)] {
+        if let Ok(mut entry) = jar.by_name(name) {
+            let mut text = String::new();
+            entry.read_to_string(&mut text).ok()?;
+            return Some((text, paper_plugin));
+        }
+    }
+
+    None
+}
+
+/// Reads a plugin jar's description, falling back to its file name
+pub fn read_plugin_jar(path: &Path, file_name: &str, enabled: bool) -> PluginInfo {
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut info = PluginInfo::unknown(file_name, enabled, size);
+
+    if let Some((text, paper_plugin)) = read_description(path) {
+        parse_description(&mut info, &text, paper_plugin);
+    }
+
+    info
+}
+
+/// Every plugin jar in `dir`, enabled or not, sorted by name
+pub fn scan_plugins_dir(dir: &Path) -> Vec<PluginInfo> {
+    let mut plugins: Vec<PluginInfo> = jar_states(dir)
+        .into_iter()
+        .filter_map(|state| {
+            let path = jar_location(dir, &state.file_name)?;
+            Some(read_plugin_jar(&path, &state.file_name, state.enabled))
+        })
+        .collect();
+
+    plugins.sort_by_key(|p| p.name.to_lowercase());
+
+    plugins
+}
+
+/// Checks
+
+fn issue(severity: IssueSeverity, kind: IssueKind, info: &PluginInfo, message: String) -> ModIssue {
+    ModIssue {
+        severity,
+        kind,
+        file_name: info.file_name.clone(),
+        mod_id: info.has_metadata.then(|| info.name.clone()),
+        message,
+    }
+}
+
+/// Checks the enabled jars in a Paper server's `plugins/`. Names are
+/// compared case-insensitively, like Paper resolves dependencies.
+pub fn check_plugins(server: &ServerConfig) -> ModCheckReport {
+    let mut report = ModCheckReport::default();
+
+    let plugins: Vec<PluginInfo> =
+        scan_plugins_dir(&PathBuf::from(&server.path).join(PLUGINS_FOLDER))
+            .into_iter()
+            .filter(|p| p.enabled)
+            .collect();
+
+    let mut owners: HashMap<String, Vec<&PluginInfo>> = HashMap::new();
+    let mut available: HashSet<String> = HashSet::new();
+
+    for info in plugins.iter().filter(|p| p.has_metadata) {
+        owners
+            .entry(info.name.to_lowercase())
+            .or_default()
+            .push(info);
+        available.insert(info.name.to_lowercase());
+        available.extend(info.provides.iter().map(|p| p.to_lowercase()));
+    }
+
+    for jars in owners.values() {
+        if jars.len() > 1 {
+            let files: Vec<&str> = jars.iter().map(|j| j.file_name.as_str()).collect();
+
+            report.add(issue(
+                IssueSeverity::Error,
+                IssueKind::DuplicateMod,
+                jars[1],
+                format!(
+                    "{} is installed more than once: {}",
+                    jars[0].name,
+                    files.join(", ")
+                ),
+            ));
+        }
+    }
+
+    for info in &plugins {
+        if !info.has_metadata {
+            report.add(issue(
+                IssueSeverity::Warning,
+                IssueKind::WrongLoader,
+                info,
+                format!("{} has no plugin.yml and won't be loaded", info.file_name),
+            ));
+            continue;
+        }
+
+        for dependency in &info.depends {
+            if !available.contains(&dependency.to_lowercase()) {
+                report.add(issue(
+                    IssueSeverity::Error,
+                    IssueKind::MissingDependency,
+                    info,
+                    format!(
+                        "{} requires {}, which isn't installed",
+                        info.name, dependency
+                    ),
+                ));
+            }
+        }
+
+        match &info.api_version {
+            // Versions that can't be compared are let through
+            Some(api)
+                if compare_versions(api, &server.version) == Some(std::cmp::Ordering::Greater) =>
+            {
+                report.add(issue(
+                    IssueSeverity::Error,
+                    IssueKind::ApiVersion,
+                    info,
+                    format!(
+                        "{} is built for Minecraft {} and won't load on {}",
+                        info.name, api, server.version
+                    ),
+                ))
+            }
+            None if !info.paper_plugin => report.add(issue(
+                IssueSeverity::Warning,
+                IssueKind::ApiVersion,
+                info,
+                format!("{} has no api-version and runs in legacy mode", info.name),
+            )),
+            _ => {}
+        }
+    }
+
+    report
+}
+
+/// Hangar
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HangarNamespace {
+    pub owner: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HangarStats {
+    #[serde(default)]
+    pub downloads: u64,
+    #[serde(default)]
+    pub stars: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarProject {
+    pub name: String,
+    pub namespace: HangarNamespace,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+
+    #[serde(default)]
+    pub stats: HangarStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HangarPagination {
+    pub limit: u32,
+    pub offset: u32,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HangarPage<T> {
+    pub pagination: HangarPagination,
+    pub result: Vec<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarFileInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub sha256_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarDownload {
+    #[serde(default)]
+    pub file_info: Option<HangarFileInfo>,
+
+    #[serde(default)]
+    pub external_url: Option<String>,
+
+    #[serde(default)]
+    pub download_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarPluginDependency {
+    pub name: String,
+    pub required: bool,
+
+    #[serde(default)]
+    pub external_url: Option<String>, // hosted outside Hangar
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarVersion {
+    pub name: String,
+    pub created_at: String,
+
+    #[serde(default)]
+    pub downloads: HashMap<String, HangarDownload>,
+
+    #[serde(default)]
+    pub plugin_dependencies: HashMap<String, Vec<HangarPluginDependency>>,
+
+    #[serde(default)]
+    pub platform_dependencies: HashMap<String, Vec<String>>,
+}
+
+fn hangar_url(path: &str, params: &[(&str, String)]) -> Result<Url, String> {
+    let base = format!("{}/api/v1{}", endpoint(Endpoint::Hangar), path);
+    Url::parse_with_params(&base, params).map_err(|e| e.to_string())
+}
+
+async fn hangar_get<T: DeserializeOwned>(client: &Client, url: Url) -> Result<T, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Hangar: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Hangar returned {}", response.status()));
+    }
+
+    response.json().await.map_err(|e| e.to_string())
+}
+
+/// Versions of a project for Paper on `version`, newest first
+async fn hangar_versions(
+    client: &Client,
+    slug: &str,
+    version: &str,
+) -> Result<Vec<HangarVersion>, String> {
+    let url = hangar_url(
+        &format!("/projects/{}/versions", slug),
+        &[
+            ("platform", HANGAR_PLATFORM.to_string()),
+            ("platformVersion", version.to_string()),
+            ("limit", "25".to_string()),
+        ],
+    )?;
+
+    hangar_get::<HangarPage<HangarVersion>>(client, url)
+        .await
+        .map(|page| page.result)
+}
+
+fn find_paper_server(server_id: &str) -> Result<ServerConfig, String> {
+    let server = find_server(server_id)?;
+
+    if server.loader != LoaderType::Paper {
+        return Err("Only Paper servers can load plugins".into());
+    }
+
+    Ok(server)
+}
+
+/// The version to install and every required Hangar dependency not installed
+/// yet, the requested one first, plus dependencies hosted elsewhere
+async fn resolve_hangar_install(
+    client: &Client,
+    server: &ServerConfig,
+    slug: &str,
+    version_name: Option<String>,
+    installed: &HashSet<String>,
+) -> Result<(Vec<(String, HangarVersion)>, Vec<String>, Vec<String>), String> {
+    let mut plan: Vec<(String, HangarVersion)> = Vec::new();
+    let mut already_installed = Vec::new();
+    let mut missing = Vec::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([(slug.to_string(), version_name)]);
+
+    while let Some((project, version_name)) = queue.pop_front() {
+        let root = plan.is_empty();
+
+        if !seen.insert(project.to_lowercase()) {
+            continue;
+        }
+
+        if installed.contains(&project.to_lowercase()) {
+            if root {
+                return Err("This plugin is already installed".into());
+            }
+
+            already_installed.push(project);
+            continue;
+        }
+
+        let versions = hangar_versions(client, &project, &server.version).await?;
+
+        let version = match &version_name {
+            Some(name) => versions.into_iter().find(|v| &v.name == name),
+            None => versions.into_iter().next(),
+        }
+        .ok_or(format!(
+            "{} has no version for Paper {}",
+            project, server.version
+        ))?;
+
+        for dependency in version
+            .plugin_dependencies
+            .get(HANGAR_PLATFORM)
+            .into_iter()
+            .flatten()
+            .filter(|d| d.required)
+        {
+            match dependency.external_url {
+                Some(_) if !installed.contains(&dependency.name.to_lowercase()) => {
+                    missing.push(dependency.name.clone())
+                }
+                Some(_) => {}
+                None => queue.push_back((dependency.name.clone(), None)),
+            }
+        }
+
+        plan.push((project, version));
+    }
+
+    Ok((plan, already_installed, missing))
+}
+
+/// Commands
+
+#[tauri::command]
+pub async fn list_plugins(server_id: String) -> Result<Vec<PluginInfo>, String> {
+    let server = find_paper_server(&server_id)?;
+    let dir = PathBuf::from(&server.path).join(PLUGINS_FOLDER);
+
+    tauri::async_runtime::spawn_blocking(move || scan_plugins_dir(&dir))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn search_hangar(
+    server_id: String,
+    query: String,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> Result<HangarPage<HangarProject>, String> {
+    let server = find_paper_server(&server_id)?;
+
+    let url = hangar_url(
+        "/projects",
+        &[
+            ("q", query),
+            ("platform", HANGAR_PLATFORM.to_string()),
+            ("version", server.version.clone()),
+            ("offset", offset.unwrap_or(0).to_string()),
+            ("limit", limit.unwrap_or(20).min(25).to_string()),
+        ],
+    )?;
+
+    hangar_get(&http_client(), url).await
+}
+
+#[tauri::command]
+pub async fn get_hangar_versions(
+    server_id: String,
+    slug: String,
+) -> Result<Vec<HangarVersion>, String> {
+    let server = find_paper_server(&server_id)?;
+    hangar_versions(&http_client(), &slug, &server.version).await
+}
+
+#[tauri::command]
+pub async fn install_hangar_plugin(
+    server_id: String,
+    slug: String,
+    version_name: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModInstallResult, String> {
+    let app = {
+        let guard = state.app_handle.lock().unwrap();
+        guard.clone().ok_or("App handle not initialized")?
+    };
+
+    let server = find_paper_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+    let plugins_dir = server_path.join(PLUGINS_FOLDER);
+    let client = http_client();
+
+    let mut sources = ModSources::load(&server_path);
+
+    // Hangar names dependencies by project, which is usually the plugin name
+    let installed: HashSet<String> = scan_plugins_dir(&plugins_dir)
+        .into_iter()
+        .filter(|p| p.has_metadata)
+        .map(|p| p.name.to_lowercase())
+        .chain(sources.files.iter().map(|f| f.project_id.to_lowercase()))
+        .collect();
+
+    let (plan, already_installed, missing_dependencies) =
+        resolve_hangar_install(&client, &server, &slug, version_name, &installed).await?;
+
+    fs::create_dir_all(&plugins_dir).map_err(|e| e.to_string())?;
+
+    let mut written: Vec<InstalledFile> = Vec::new();
+    let mut created: Vec<PathBuf> = Vec::new(); // only these are removed on rollback
+
+    for (i, (project, version)) in plan.iter().enumerate() {
+        let result = async {
+            let download = version
+                .downloads
+                .get(HANGAR_PLATFORM)
+                .ok_or(format!("{} has no Paper download", project))?;
+
+            let (Some(url), Some(file)) = (&download.download_url, &download.file_info) else {
+                return Err(match &download.external_url {
+                    Some(external) => format!("{} is only available from {}", project, external),
+                    None => format!("{} has no download", project),
+                });
+            };
+
+            // Names come from the API, keep them inside plugins/
+            if file.name.contains(['/', '\\']) || file.name.starts_with('.') {
+                return Err(format!("Invalid file name {}", file.name));
+            }
+
+            // Never overwrite a jar someone put there, the rollback would lose it
+            let dest = plugins_dir.join(&file.name);
+            if dest.exists() {
+                let sha256 = hash_file::<Sha256>(&dest).ok();
+                if !sha256.is_some_and(|h| h.eq_ignore_ascii_case(&file.sha256_hash)) {
+                    return Err(format!(
+                        "{} already exists in {}, remove it first",
+                        file.name, PLUGINS_FOLDER
+                    ));
+                }
+            } else {
+                let progress = DownloadEvents::new(app.clone(), file.name.clone());
+                cached_download(
+                    &client,
+                    url,
+                    &dest,
+                    Some(&Checksum::Sha256(file.sha256_hash.clone())),
+                    &progress,
+                )
+                .await?;
+
+                created.push(dest.clone());
+            }
+
+            Ok(InstalledFile {
+                folder: PLUGINS_FOLDER.to_string(),
+                file_name: file.name.clone(),
+                provider: ModProvider::Hangar,
+                project_id: project.clone(),
+                version_id: version.name.clone(),
+                version_number: version.name.clone(),
+                sha1: hash_file::<Sha1>(&dest)?,
+                installed_at: Utc::now().timestamp(),
+                dependency: i > 0,
+            })
+        }
+        .await;
+
+        match result {
+            Ok(file) => written.push(file),
+            Err(e) => {
+                // All or nothing, a plugin without its dependencies won't enable
+                for path in &created {
+                    fs::remove_file(path).ok();
+                }
+
+                return Err(format!("Failed to install {}: {}", project, e));
+            }
+        }
+    }
+
+    for file in &written {
+        sources.record(file.clone());
+    }
+    sources.save(&server_path)?;
+
+    Ok(ModInstallResult {
+        installed: written,
+        already_installed,
+        missing_dependencies,
+    })
+}
@@ -6,40 +6,78 @@ use serde::{ser, Deserialize, Serialize};
 use serde_json::Value;
 use std::f32::consts::E;
 use std::fmt::format;
-use std::process::Command;
+use std::io::BufRead;
+use std::process::{Command, Stdio};
 use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
-use crate::commands::server_management::{ServerConfig, TunnelConfig, TunnelProvider};
-use crate::utils::path::{cleanup_empty_parent_dir, cleanup_server_dir, servers_dir};
+use crate::commands::artifact_cache::{seed_libraries, store_libraries};
+use crate::commands::creation_jobs::{CreatePhase, CreationJob, CANCELLED};
+use crate::commands::manifest_cache::{fetch_cached, fetch_json_cached};
+use crate::commands::java_manager::{ensure_java, server_java};
+use crate::commands::curseforge_import::{install_curseforge_files, CfPack};
+use crate::commands::modpack_import::{install_mrpack_files, MrPack};
+use crate::commands::server_templates::{instantiate_template, load_template, ServerTemplate};
+use crate::commands::server_management::{
+    ensure_name_available, validate_server_name, ServerConfig, TunnelConfig, TunnelProvider,
+};
+use crate::state::app_state::AppState;
+use crate::commands::versions_loaders::{
+    bedrock_version_from_url, fetch_bedrock_download_link, fetch_fabric_installers,
+    fetch_fabric_loaders, fetch_forge_builds, fetch_neoforge_builds, fetch_version_manifest,
+    ForgeBuild,
+};
+use crate::utils::download::{fetch_maven_sha1, Checksum, DownloadObserver};
+use crate::utils::network::{endpoint, http_client, Endpoint};
+use crate::utils::path::{cleanup_server_dir, unique_server_dir};
 
 #[derive(Deserialize, Debug)]
 struct VersionDetails {
     downloads: Downloads,
+
+    #[serde(rename = "javaVersion")]
+    java_version: Option<JavaVersionInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JavaVersionInfo {
+    #[serde(rename = "majorVersion")]
+    major_version: u32,
 }
 
 #[derive(Deserialize, Debug)]
 struct Downloads {
-    server: DownloadInfo,
+    // Old alphas/betas and some snapshots ship no server jar
+    server: Option<DownloadInfo>,
 }
 
 #[derive(Deserialize, Debug)]
 struct DownloadInfo {
     url: String,
+    sha1: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LoaderType {
     Vanilla,
     Fabric,
     Forge,
+    NeoForge,
+    Paper,
+    Bedrock,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateServerResult {
     pub success: bool,
     pub path: String,
+    pub job_id: String,
+
+    // Modpack files that couldn't be downloaded, the server was created without them
+    #[serde(default)]
+    pub failed_files: Vec<String>,
 }
 
 const DEFAULT_SERVER_PROPERTIES: &str = r#"
@@ -58,60 +96,187 @@ motd=A Minecraft Server
 server-port=25565
 "#;
 
+const DEFAULT_BEDROCK_SERVER_PROPERTIES: &str = r#"
+# Generated by Cubely
+server-name=A Minecraft Server
+gamemode=survival
+force-gamemode=false
+difficulty=easy
+allow-cheats=false
+max-players=10
+online-mode=true
+allow-list=false
+server-port=19132
+server-portv6=19133
+enable-lan-visibility=true
+view-distance=32
+tick-distance=4
+player-idle-timeout=30
+max-threads=8
+level-name=Bedrock level
+level-seed=
+default-player-permission-level=member
+texturepack-required=false
+"#;
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // one argument per field the frontend sends
 pub async fn create_server(
     name: String,
     version: String,
     loader: LoaderType,
     ram_gb: u8,
+    loader_build: Option<String>,
+    installer_version: Option<String>,
+    template_id: Option<String>,
+    job_id: String,
+    state: tauri::State<'_, AppState>,
 ) -> Result<CreateServerResult, String> {
-    let mut server_path = servers_dir();
-    server_path.push(&version);
-    server_path.push(&name);
+    let source = match template_id {
+        Some(template_id) => {
+            // Templates carry their own server files, so they must match what was asked for
+            let template = load_template(&template_id)?;
+
+            if template.version != version || template.loader != loader {
+                return Err(format!(
+                    "Template {} is for {:?} {}",
+                    template.name, template.loader, template.version
+                ));
+            }
 
-    if server_path.exists() {
-        return Err("Server already exists".into());
-    }
+            ServerSource::Template(template)
+        }
+        None => ServerSource::Install {
+            loader_build,
+            installer_version,
+        },
+    };
 
-    fs::create_dir_all(&server_path).map_err(|e| e.to_string())?;
+    create_server_from(name, version, loader, ram_gb, source, job_id, &state).await
+}
 
-    let result: Result<(), String> = async {
-        match loader {
-            LoaderType::Vanilla => {
-                create_vanilla_server(&name, &version, &server_path).await?;
-            }
-            LoaderType::Fabric => {
-                create_fabric_server(&version, &server_path).await?;
-                fs::create_dir_all(server_path.join("mods")).ok();
-            }
-            LoaderType::Forge => {
-                create_forge_server(&version, &server_path).await?;
-                fs::create_dir_all(server_path.join("mods")).ok();
-            }
+/// Where a new server's files come from
+pub enum ServerSource {
+    Install {
+        loader_build: Option<String>,
+        installer_version: Option<String>,
+    },
+    Template(ServerTemplate),
+    Modrinth(MrPack),
+    CurseForge(CfPack),
+}
+
+/// Creates a server as a job, rolling the directory back on failure or cancellation
+pub async fn create_server_from(
+    name: String,
+    version: String,
+    loader: LoaderType,
+    ram_gb: u8,
+    source: ServerSource,
+    job_id: String,
+    state: &AppState,
+) -> Result<CreateServerResult, String> {
+    let app = {
+        let guard = state.app_handle.lock().unwrap();
+        guard
+            .clone()
+            .ok_or("App handle not initialized")?
+    };
+
+    let java_base = {
+        let guard = state.java_base_dir.lock().unwrap();
+        guard
+            .clone()
+            .ok_or("Java base directory not initialized")?
+    };
+
+    let name = validate_server_name(&name)?;
+    ensure_name_available(&name, None)?;
+
+    // Folder is a slug of the name, the name itself only lives in cubely.json
+    let server_path = unique_server_dir(&name, None);
+
+    // The frontend picks the job id up front so it can cancel before we return
+    let job = CreationJob::new(job_id, app);
+    state
+        .creation_jobs
+        .lock()
+        .unwrap()
+        .insert(job.id.clone(), job.clone());
+
+    let mut failed_files = Vec::new();
+
+    let result = match &source {
+        ServerSource::Install {
+            loader_build,
+            installer_version,
+        } => {
+            let target = InstallTarget {
+                name: &name,
+                version: &version,
+                loader: &loader,
+                loader_build: loader_build.as_deref(),
+                installer_version: installer_version.as_deref(),
+            };
+            create_server_job(&target, &server_path, &java_base, &job).await
+        }
+        ServerSource::Template(template) => instantiate_template(template, &server_path, &job),
+        ServerSource::Modrinth(pack) => {
+            let target = InstallTarget {
+                name: &name,
+                version: &version,
+                loader: &loader,
+                loader_build: pack.loader_build.as_deref(),
+                installer_version: None,
+            };
+            let files = install_mrpack_files(pack, &server_path, &job);
+            create_pack_server(&target, files, &server_path, &java_base, &job)
+                .await
+                .map(|(installed, failed)| {
+                    failed_files = failed;
+                    installed
+                })
         }
+        ServerSource::CurseForge(pack) => {
+            let target = InstallTarget {
+                name: &name,
+                version: &version,
+                loader: &loader,
+                loader_build: pack.loader_build.as_deref(),
+                installer_version: None,
+            };
+            let files = install_curseforge_files(pack, &server_path, &job);
+            create_pack_server(&target, files, &server_path, &java_base, &job)
+                .await
+                .map(|(installed, failed)| {
+                    failed_files = failed;
+                    installed
+                })
+        }
+    };
 
-        // Write server.properties and eula only after successful install
-        fs::write(
-            server_path.join("server.properties"),
-            DEFAULT_SERVER_PROPERTIES,
-        )
-        .map_err(|e| e.to_string())?;
-        fs::write(server_path.join("eula.txt"), "eula=true\n").map_err(|e| e.to_string())?;
+    state.creation_jobs.lock().unwrap().remove(&job.id);
 
-        Ok(())
-    }
-    .await;
+    // Rollback on failure or cancellation
+    let installed = match result {
+        Ok(installed) => installed,
+        Err(err) => {
+            cleanup_server_dir(&server_path);
 
-    // Rollback on failure
-    if let Err(err) = result {
-        cleanup_server_dir(&server_path);
+            if job.is_cancelled() {
+                job.set_phase(CreatePhase::Cancelled);
+                return Err(CANCELLED.into());
+            }
 
-        if let Some(version_dir) = server_path.parent() {
-            cleanup_empty_parent_dir(&version_dir.to_path_buf());
+            job.set_phase(CreatePhase::Failed);
+            return Err(err);
         }
+    };
 
-        return Err(err);
-    }
+    let (server_jar, crossplay) = match source {
+        ServerSource::Template(template) => (template.server_jar, template.crossplay),
+        _ => (None, None),
+    };
 
     let config = ServerConfig {
         id: Uuid::new_v4().to_string(),
@@ -121,10 +286,16 @@ pub async fn create_server(
         ram_gb,
         path: server_path.to_string_lossy().to_string(),
         created_at: Utc::now().timestamp(),
+        java_major: installed.java_major,
+        loader_build: installed.loader_build,
+        installer_version: installed.installer_version,
+        server_jar,
         tunnel: Some(TunnelConfig {
             enabled: false,
             provider: TunnelProvider::Playit,
         }),
+        crossplay,
+        block_on_mod_errors: false,
     };
 
     fs::write(
@@ -133,215 +304,624 @@ pub async fn create_server(
     )
     .map_err(|e| e.to_string())?;
 
+    job.set_phase(CreatePhase::Done);
+
     Ok(CreateServerResult {
         success: true,
         path: server_path.to_string_lossy().to_string(),
+        job_id: job.id.clone(),
+        failed_files,
     })
 }
 
-pub async fn create_vanilla_server(
-    name: &str,
-    version: &str,
+/// What an install settled on, recorded in cubely.json
+pub struct InstalledServer {
+    pub java_major: Option<u32>,      // Java major version Mojang requires, None for Bedrock
+    pub loader_build: Option<String>, // Fabric loader or Forge build that was installed
+    pub installer_version: Option<String>, // Fabric installer that ran
+}
+
+/// Installs the loader a modpack needs, then the pack's own files.
+/// Returns the install and the pack files that couldn't be downloaded.
+async fn create_pack_server(
+    target: &InstallTarget<'_>,
+    install_files: impl std::future::Future<Output = Result<Vec<String>, String>>,
     server_path: &PathBuf,
-) -> Result<(), String> {
-    // Fetch version manifest
-    let client = Client::new();
+    java_base: &PathBuf,
+    job: &CreationJob,
+) -> Result<(InstalledServer, Vec<String>), String> {
+    let installed = create_server_job(target, server_path, java_base, job).await?;
+    let failed_files = install_files.await?;
 
-    let manifest: serde_json::Value = client
-        .get("https://launchermeta.mojang.com/mc/game/version_manifest.json")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
+    Ok((installed, failed_files))
+}
 
-    let versions = manifest["versions"].as_array().ok_or("Invalid Manifest")?;
+/// Installs the server files. Any error, including cancellation, triggers a rollback.
+async fn create_server_job(
+    target: &InstallTarget<'_>,
+    server_path: &PathBuf,
+    java_base: &PathBuf,
+    job: &CreationJob,
+) -> Result<InstalledServer, String> {
+    let installed = install_server_files(target, server_path, java_base, job).await?;
 
-    // Find the selected version
-    let version_url = versions
-        .iter()
-        .find(|v| v["id"] == version)
-        .and_then(|v| v["url"].as_str())
-        .ok_or("Version not found")?;
+    job.check_cancelled()?;
+    job.set_phase(CreatePhase::WritingFiles);
 
-    // Fetch version details
-    let details: VersionDetails = client
-        .get(version_url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json()
-        .await
+    // Write server.properties and eula only after successful install
+    // Bedrock has its own property keys and no eula.txt
+    if let LoaderType::Bedrock = target.loader {
+        fs::write(
+            server_path.join("server.properties"),
+            DEFAULT_BEDROCK_SERVER_PROPERTIES,
+        )
+        .map_err(|e| e.to_string())?;
+    } else {
+        fs::write(
+            server_path.join("server.properties"),
+            DEFAULT_SERVER_PROPERTIES,
+        )
         .map_err(|e| e.to_string())?;
+        fs::write(server_path.join("eula.txt"), "eula=true\n").map_err(|e| e.to_string())?;
+    }
+
+    Ok(installed)
+}
+
+/// What to install: the server's name, Minecraft version and loader,
+/// plus the optional loader build and Fabric installer to pin.
+#[derive(Clone, Copy)]
+pub struct InstallTarget<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+    pub loader: &'a LoaderType,
+    pub loader_build: Option<&'a str>,
+    pub installer_version: Option<&'a str>,
+}
+
+/// Installs the server jar or binary and loader into `server_path`,
+/// leaving worlds and configuration alone. Shared with upgrades.
+pub async fn install_server_files(
+    target: &InstallTarget<'_>,
+    server_path: &PathBuf,
+    java_base: &PathBuf,
+    job: &CreationJob,
+) -> Result<InstalledServer, String> {
+    let InstallTarget {
+        name,
+        version,
+        loader,
+        loader_build,
+        installer_version,
+    } = *target;
+
+    if installer_version.is_some() && !matches!(loader, LoaderType::Fabric) {
+        return Err("Only Fabric servers can be created with a specific installer version".into());
+    }
+
+    if loader_build.is_some()
+        && !matches!(loader, LoaderType::Fabric | LoaderType::Forge | LoaderType::NeoForge)
+    {
+        return Err("Only Fabric, Forge and NeoForge servers can be created with a specific loader build".into());
+    }
+
+    fs::create_dir_all(server_path).map_err(|e| e.to_string())?;
+
+    let mut installed_build = None;
+    let mut installed_installer = None;
+
+    // Java requirement from Mojang's version JSON, None for Bedrock
+    let mut java_major = None;
+
+    match loader {
+        LoaderType::Vanilla => {
+            java_major = create_vanilla_server(name, version, server_path, job).await?;
+        }
+        LoaderType::Fabric => {
+            java_major = fetch_java_major(version).await;
+            let java = install_installer_java(java_base, java_major, version, job).await?;
+            let (loader, installer) = create_fabric_server(
+                version,
+                loader_build,
+                installer_version,
+                server_path,
+                &java,
+                job,
+            )
+            .await?;
+            installed_build = Some(loader);
+            installed_installer = Some(installer);
+            fs::create_dir_all(server_path.join("mods")).ok();
+        }
+        LoaderType::Forge => {
+            java_major = fetch_java_major(version).await;
+            let java = install_installer_java(java_base, java_major, version, job).await?;
+            let build = create_forge_server(version, loader_build, server_path, &java, job).await?;
+            installed_build = Some(build);
+            fs::create_dir_all(server_path.join("mods")).ok();
+        }
+        LoaderType::NeoForge => {
+            java_major = fetch_java_major(version).await;
+            let java = install_installer_java(java_base, java_major, version, job).await?;
+            let build = create_neoforge_server(version, loader_build, server_path, &java, job).await?;
+            installed_build = Some(build);
+            fs::create_dir_all(server_path.join("mods")).ok();
+        }
+        LoaderType::Paper => {
+            java_major = fetch_java_major(version).await;
+            create_paper_server(version, server_path, job).await?;
+            fs::create_dir_all(server_path.join("plugins")).ok();
+        }
+        LoaderType::Bedrock => {
+            create_bedrock_server(version, server_path, job).await?;
+        }
+    }
+
+    Ok(InstalledServer {
+        java_major,
+        loader_build: installed_build,
+        installer_version: installed_installer,
+    })
+}
+
+async fn install_installer_java(
+    java_base: &PathBuf,
+    java_major: Option<u32>,
+    version: &str,
+    job: &CreationJob,
+) -> Result<PathBuf, String> {
+    job.check_cancelled()?;
+    job.set_phase(CreatePhase::InstallingJava);
+
+    ensure_java(java_base, server_java(java_major, version), job).await
+}
+
+/// Downloads the vanilla server.jar, returning the Java major version Mojang requires for it
+pub async fn create_vanilla_server(
+    name: &str,
+    version: &str,
+    server_path: &PathBuf,
+    job: &CreationJob,
+) -> Result<Option<u32>, String> {
+    let client = http_client();
+
+    let details = fetch_mojang_version_details(&client, version).await?;
 
     println!("{:?}", details);
 
+    let java_major = details.java_version.map(|j| j.major_version);
+
     // Extract server download URL
-    let server_url = details.downloads.server.url;
+    let server = details.downloads.server.ok_or(format!(
+        "Minecraft {} has no dedicated server download from Mojang",
+        version
+    ))?;
 
     // Download server.jar
     let jar_path = server_path.join("server.jar");
-    let bytes = client
-        .get(server_url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .bytes()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    fs::write(&jar_path, bytes).map_err(|e| e.to_string())?;
+    let checksum = server.sha1.map(Checksum::Sha1);
+    job.download(&client, &server.url, &jar_path, checksum.as_ref()).await?;
 
-    Ok(())
+    Ok(java_major)
 }
 
-pub async fn create_fabric_server(version: &str, server_path: &PathBuf) -> Result<(), String> {
-    let client = Client::new();
+async fn fetch_mojang_version_details(
+    client: &Client,
+    version: &str,
+) -> Result<VersionDetails, String> {
+    // Fetch version manifest
+    let manifest = fetch_version_manifest(client).await?.data;
 
-    // Fetch latest Fabric installer info
-    let installers: Value = client
-        .get("https://meta.fabricmc.net/v2/versions/installer")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch Fabric installer list: {}", e))?
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Fabric installer list: {}", e))?;
+    let versions = manifest["versions"].as_array().ok_or("Invalid Manifest")?;
 
-    let installer = installers
-        .as_array()
-        .and_then(|arr| arr.first())
-        .ok_or("No Fabric installer versions found")?;
+    // Find the selected version
+    let version_url = versions
+        .iter()
+        .find(|v| v["id"] == version)
+        .and_then(|v| v["url"].as_str())
+        .ok_or(format!("Minecraft {} is not in the Mojang version manifest", version))?;
 
-    let installer_version = installer["version"]
-        .as_str()
-        .ok_or("Invalid Fabric installer version")?;
+    // Fetch version details, cached so servers can be created offline
+    let details = fetch_json_cached(client, version_url).await?.data;
+
+    serde_json::from_value(details).map_err(|e| e.to_string())
+}
+
+// Loader installs don't need Mojang's version JSON, so a lookup failure only
+// means falling back to require_java's guess at start
+async fn fetch_java_major(version: &str) -> Option<u32> {
+    fetch_mojang_version_details(&http_client(), version)
+        .await
+        .ok()?
+        .java_version
+        .map(|j| j.major_version)
+}
+
+/// Installs Fabric, returning the loader and installer versions that were used
+pub async fn create_fabric_server(
+    version: &str,
+    loader: Option<&str>,
+    installer: Option<&str>,
+    server_path: &PathBuf,
+    java: &PathBuf,
+    job: &CreationJob,
+) -> Result<(String, String), String> {
+    let client = http_client();
+
+    // Pin the loader so cubely.json records what was installed
+    let loaders = fetch_fabric_loaders(version).await?;
+    let loader = match loader {
+        Some(loader) => loaders
+            .iter()
+            .find(|l| l.version == loader)
+            .ok_or(format!(
+                "Fabric loader {} is not available for Minecraft {}",
+                loader, version
+            ))?,
+        None => loaders
+            .iter()
+            .find(|l| l.stable)
+            .or(loaders.first())
+            .ok_or(format!("No Fabric loader found for Minecraft {}", version))?,
+    }
+    .version
+    .clone();
+
+    // The requested installer, or the latest stable one
+    let installers = fetch_fabric_installers().await?;
+    let installer_version = match installer {
+        Some(installer) => installers
+            .iter()
+            .find(|i| i.version == installer)
+            .ok_or(format!("Fabric installer {} is not available", installer))?,
+        None => installers
+            .iter()
+            .find(|i| i.stable)
+            .or(installers.first())
+            .ok_or("No Fabric installer versions found")?,
+    }
+    .version
+    .clone();
 
     let installer_url = format!(
-        "https://maven.fabricmc.net/net/fabricmc/fabric-installer/{0}/fabric-installer-{0}.jar",
+        "{0}/net/fabricmc/fabric-installer/{1}/fabric-installer-{1}.jar",
+        endpoint(Endpoint::FabricMaven),
         installer_version
     );
 
     // Download Fabric installer
     let installer_path = server_path.join("fabric-installer.jar");
 
-    let bytes = client
-        .get(&installer_url)
-        .send()
+    let checksum = fetch_maven_sha1(&client, &installer_url).await;
+    job.download(&client, &installer_url, &installer_path, checksum.as_ref())
         .await
-        .map_err(|e| format!("Failed to download Fabric installer: {}", e))?
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read Fabric installer bytes: {}", e))?;
+        .map_err(|e| format!("Failed to download Fabric installer: {}", e))?;
 
-    fs::write(&installer_path, bytes)
-        .map_err(|e| format!("Failed to write Fabric installer: {}", e))?;
+    seed_libraries(server_path);
 
     // Run Fabric installer
-    let status = Command::new("java")
-        .arg("-jar")
-        .arg("fabric-installer.jar")
-        .arg("server")
-        .arg("-mcversion")
-        .arg(version)
-        .arg("-downloadMinecraft")
-        .current_dir(server_path)
-        .status()
-        .map_err(|e| format!("Failed to run Fabric installer (is Java installed?): {}", e))?;
-
     // java -jar fabric-installer.jar server \
     //     -mcversion 1.21.1 \
+    //     -loader 0.16.10 \
     //     -downloadMinecraft
+    let success = run_installer(
+        java,
+        "fabric-installer.jar",
+        &[
+            "server",
+            "-mcversion",
+            version,
+            "-loader",
+            &loader,
+            "-downloadMinecraft",
+        ],
+        server_path,
+        job,
+    )
+    .await
+    .map_err(|e| format!("Failed to run Fabric installer: {}", e))?;
 
-    if !status.success() {
+    if !success {
         return Err("Fabric installer failed".into());
     }
 
+    store_libraries(server_path);
+
     fs::remove_file(installer_path).ok();
 
-    Ok(())
+    Ok((loader, installer_version))
 }
 
-pub async fn create_forge_server(version: &str, server_path: &PathBuf) -> Result<(), String> {
-    let client = Client::new();
+/// Installs Forge, returning the Forge build that was installed
+pub async fn create_forge_server(
+    version: &str,
+    build: Option<&str>,
+    server_path: &PathBuf,
+    java: &PathBuf,
+    job: &CreationJob,
+) -> Result<String, String> {
+    let client = http_client();
 
     // Resolve real Forge version
-    let forge_version = resolve_latest_forge_build(version).await?;
+    let forge_build = resolve_forge_build(version, build).await?;
+    let forge_version = &forge_build.version;
 
     // Build Forge installer URL
     let installer_url = format!(
-        "https://maven.minecraftforge.net/net/minecraftforge/forge/{0}/forge-{0}-installer.jar",
+        "{0}/net/minecraftforge/forge/{1}/forge-{1}-installer.jar",
+        endpoint(Endpoint::ForgeMaven),
         forge_version
     );
 
     let installer_path = server_path.join("forge-installer.jar");
 
     // Download Forge installer
-    let bytes = client
-        .get(&installer_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download Forge installer: {}", e))?
-        .bytes()
+    let checksum = fetch_maven_sha1(&client, &installer_url).await;
+    let size = job
+        .download(&client, &installer_url, &installer_path, checksum.as_ref())
         .await
-        .map_err(|e| format!("Failed to read Forge installer bytes: {}", e))?;
+        .map_err(|e| format!("Failed to download Forge installer: {}", e))?;
 
     // Safety check
-    if bytes.len() < 1_000_000 {
+    if size < 1_000_000 {
         return Err(format!(
             "Invalid Forge installer downloaded for {}",
             forge_version
         ));
     }
 
-    println!("Downloaded forge installer size: {} bytes", bytes.len());
+    println!("Downloaded forge installer size: {} bytes", size);
 
-    fs::write(&installer_path, bytes)
-        .map_err(|e| format!("Failed to write Forge installer: {}", e))?;
+    seed_libraries(server_path);
 
     // Run Forge installer
-    let status = Command::new("java")
-        .arg("-jar")
-        .arg("forge-installer.jar")
-        .arg("--installServer")
-        .current_dir(server_path)
-        .status()
-        .map_err(|e| format!("Failed to run Forge installer (is Java installed?): {}", e))?;
+    let success = run_installer(
+        java,
+        "forge-installer.jar",
+        &["--installServer"],
+        server_path,
+        job,
+    )
+    .await
+    .map_err(|e| format!("Failed to run Forge installer: {}", e))?;
 
-    if !status.success() {
+    if !success {
         return Err("Forge installer failed".into());
     }
 
+    store_libraries(server_path);
+
     fs::remove_file(installer_path).ok();
 
-    Ok(())
+    Ok(forge_build.build)
 }
 
-async fn resolve_latest_forge_build(version: &str) -> Result<String, String> {
-    let text = reqwest::get(
-        "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml",
+/// Installs NeoForge with its installer, returning the build that was installed.
+/// NeoForge ships no launch jar, servers start from its argument files instead.
+pub async fn create_neoforge_server(
+    version: &str,
+    build: Option<&str>,
+    server_path: &PathBuf,
+    java: &PathBuf,
+    job: &CreationJob,
+) -> Result<String, String> {
+    let client = http_client();
+
+    let builds = fetch_neoforge_builds(version).await?;
+
+    let build = match build {
+        Some(build) => builds
+            .iter()
+            .find(|b| *b == build)
+            .ok_or(format!(
+                "NeoForge {} is not available for Minecraft {}",
+                build, version
+            ))?,
+        // Newest stable build, betas only when nothing else exists
+        None => builds
+            .iter()
+            .find(|b| !b.contains("beta"))
+            .or_else(|| builds.first())
+            .ok_or(format!("No NeoForge build found for Minecraft {}", version))?,
+    }
+    .clone();
+
+    let installer_url = format!(
+        "{0}/net/neoforged/neoforge/{1}/neoforge-{1}-installer.jar",
+        endpoint(Endpoint::NeoForgeMaven),
+        build
+    );
+
+    let installer_path = server_path.join("neoforge-installer.jar");
+
+    let checksum = fetch_maven_sha1(&client, &installer_url).await;
+    job.download(&client, &installer_url, &installer_path, checksum.as_ref())
+        .await
+        .map_err(|e| format!("Failed to download NeoForge installer: {}", e))?;
+
+    seed_libraries(server_path);
+
+    let success = run_installer(
+        java,
+        "neoforge-installer.jar",
+        &["--installServer"],
+        server_path,
+        job,
     )
     .await
-    .map_err(|e| e.to_string())?
-    .text()
+    .map_err(|e| format!("Failed to run NeoForge installer: {}", e))?;
+
+    if !success {
+        return Err("NeoForge installer failed".into());
+    }
+
+    store_libraries(server_path);
+
+    fs::remove_file(installer_path).ok();
+
+    Ok(build)
+}
+
+pub async fn create_paper_server(
+    version: &str,
+    server_path: &PathBuf,
+    job: &CreationJob,
+) -> Result<(), String> {
+    let client = http_client();
+
+    // Latest build for the version; Fill API rejects requests without a User-Agent
+    let build = fetch_json_cached(
+        &client,
+        &format!(
+            "{}/v3/projects/paper/versions/{}/builds/latest",
+            endpoint(Endpoint::PaperApi),
+            version
+        ),
+    )
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("Failed to fetch Paper builds: {}", e))?
+    .data;
 
-    for line in text.lines() {
-        let line = line.trim();
+    let download = &build["downloads"]["server:default"];
 
-        if line.starts_with("<version>") && line.ends_with("</version>") {
-            let full = line.replace("<version>", "").replace("</version>", "");
+    let jar_url = download["url"]
+        .as_str()
+        .ok_or(format!("No Paper build found for Minecraft {}", version))?;
 
-            let parts: Vec<&str> = full.splitn(2, '-').collect();
-            let mc_version = parts[0];
+    let checksum = download["checksums"]["sha256"]
+        .as_str()
+        .map(|h| Checksum::Sha256(h.to_string()));
 
-            if mc_version == version {
-                return Ok(full);
-            }
+    job.download(
+        &client,
+        jar_url,
+        &server_path.join("server.jar"),
+        checksum.as_ref(),
+    )
+    .await
+    .map_err(|e| format!("Failed to download Paper: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub async fn create_bedrock_server(_: &str, _: &PathBuf, _: &CreationJob) -> Result<(), String> {
+    Err("Bedrock Dedicated Server is only supported on Linux.".into())
+}
+
+#[cfg(target_os = "linux")]
+pub async fn create_bedrock_server(
+    version: &str,
+    server_path: &PathBuf,
+    job: &CreationJob,
+) -> Result<(), String> {
+    let download_url = resolve_bedrock_download_url(version).await?;
+
+    // Mojang publishes no checksum for Bedrock server archives
+    let zip_path = server_path.join("bedrock-server.zip");
+    job.download(&http_client(), &download_url, &zip_path, None)
+        .await
+        .map_err(|e| format!("Failed to download Bedrock server {}: {}", version, e))?;
+
+    job.set_phase(CreatePhase::Installing);
+
+    let file = fs::File::open(&zip_path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    archive
+        .extract(server_path)
+        .map_err(|e| format!("Failed to extract Bedrock server: {}", e))?;
+
+    fs::remove_file(&zip_path).ok();
+
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = server_path.join("bedrock_server");
+        if !bin.exists() {
+            return Err("Bedrock server archive did not contain bedrock_server".into());
         }
+
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).ok();
+    }
+
+    Ok(())
+}
+
+async fn resolve_bedrock_download_url(version: &str) -> Result<String, String> {
+    let latest = fetch_bedrock_download_link().await?;
+
+    if version == "latest" || bedrock_version_from_url(&latest).as_deref() == Some(version) {
+        return Ok(latest);
+    }
+
+    // Older builds stay available under the same naming scheme
+    Ok(format!(
+        "{}/bin-linux/bedrock-server-{}.zip",
+        endpoint(Endpoint::BedrockDownloads),
+        version
+    ))
+}
+
+/// Runs a loader installer jar with the managed Java runtime, streaming its
+/// output as creation progress. Returns whether it succeeded.
+async fn run_installer(
+    java: &PathBuf,
+    jar: &str,
+    args: &[&str],
+    server_path: &PathBuf,
+    job: &CreationJob,
+) -> Result<bool, String> {
+    job.check_cancelled()?;
+    job.set_phase(CreatePhase::Installing);
+
+    let mut child = Command::new(java)
+        .arg("-jar")
+        .arg(jar)
+        .args(args)
+        .current_dir(server_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(stdout) = child.stdout.take() {
+        let job = job.clone();
+
+        std::thread::spawn(move || {
+            let reader = std::io::BufReader::new(stdout);
+            for line in reader.lines().flatten() {
+                job.log(line);
+            }
+        });
+    }
+
+    if let Some(stderr) = child.stderr.take() {
+        let job = job.clone();
+
+        std::thread::spawn(move || {
+            let reader = std::io::BufReader::new(stderr);
+            for line in reader.lines().flatten() {
+                job.log(format!("[ERR] {}", line));
+            }
+        });
     }
 
-    Err(format!("No Forge build found for Minecraft {}", version))
+    job.wait_installer(child).await
+}
+
+/// Maven version of the requested Forge build, or the recommended one
+async fn resolve_forge_build(version: &str, build: Option<&str>) -> Result<ForgeBuild, String> {
+    let builds = fetch_forge_builds(version).await?;
+
+    let found = match build {
+        Some(build) => builds.find(build).ok_or(format!(
+            "Forge {} is not available for Minecraft {}",
+            build, version
+        ))?,
+        None => builds
+            .default_build()
+            .ok_or(format!("No Forge build found for Minecraft {}", version))?,
+    };
+
+    Ok(found.clone())
 }
@@ -0,0 +1,418 @@
+use std::{
+    cmp::Ordering,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::commands::mod_checker::compare_versions;
+use crate::commands::server_creation::LoaderType;
+use crate::commands::versions_loaders::neoforge_mc_version;
+use crate::commands::server_management::{
+    ensure_name_available, save_server_config, validate_server_name, ServerConfig, TunnelConfig,
+    TunnelProvider,
+};
+use crate::utils::path::{copy_dir_all, servers_dir, unique_server_dir};
+
+/// IMPORTING EXISTING SERVERS
+///
+/// Adopts a server directory that wasn't created by Cubely. The loader and
+/// Minecraft version are read from the files each loader leaves behind, then
+/// the directory is moved or linked under `servers_dir()` and gets a cubely.json.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedServer {
+    pub name: String, // folder name, suggested as the display name
+    pub loader: LoaderType,
+    pub version: Option<String>,
+    pub loader_build: Option<String>,
+    pub server_jar: Option<String>,
+    pub java_major: Option<u32>,
+    pub ram_gb: u8,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    #[default]
+    Link, // symlink under servers_dir(), files stay where they are
+    Move,
+}
+
+/// Detection
+
+/// Jars in the top level of the server directory
+fn root_jars(dir: &Path) -> Vec<String> {
+    let mut jars: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|n| n.ends_with(".jar"))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    jars.sort();
+    jars
+}
+
+fn sorted_dir_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    names.sort();
+    names
+}
+
+/// Newest of `names` by the version `key` picks out of each, so "0.16.10"
+/// beats "0.16.9". Names it can't parse fall back to plain string order.
+fn newest_version(names: Vec<String>, key: impl Fn(&str) -> &str) -> Option<String> {
+    names
+        .into_iter()
+        .max_by(|a, b| compare_versions(key(a), key(b)).unwrap_or_else(|| a.cmp(b)))
+}
+
+/// Minecraft version and Java major version from the version.json that
+/// server jars embed since 1.14
+fn jar_version(jar: &Path) -> Option<(String, Option<u32>)> {
+    let file = fs::File::open(jar).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut entry = archive.by_name("version.json").ok()?;
+
+    let mut raw = String::new();
+    entry.read_to_string(&mut raw).ok()?;
+
+    let json: Value = serde_json::from_str(&raw).ok()?;
+    let id = json["id"].as_str().or(json["name"].as_str())?.to_string();
+    let java_major = json["java_version"].as_u64().map(|v| v as u32);
+
+    Some((id, java_major))
+}
+
+/// First version.json found among the root jars
+fn any_jar_version(dir: &Path, jars: &[String]) -> Option<(String, Option<u32>)> {
+    jars.iter().find_map(|jar| jar_version(&dir.join(jar)))
+}
+
+// "git-Paper-196 (MC: 1.20.1)" or "1.21.4-232-main@abc1234 (2025-01-01T00:00:00Z)"
+fn paper_version(dir: &Path) -> Option<String> {
+    let raw = fs::read_to_string(dir.join("version_history.json")).ok()?;
+    let json: Value = serde_json::from_str(&raw).ok()?;
+    let current = json["currentVersion"].as_str()?;
+
+    if let Some(start) = current.find("(MC: ") {
+        let rest = &current[start + 5..];
+        return rest.split(')').next().map(|v| v.trim().to_string());
+    }
+
+    current.split('-').next().map(String::from)
+}
+
+// "libraries/net/minecraftforge/forge/1.20.1-47.2.0" -> ("1.20.1", "47.2.0")
+// Old builds end in the game version again: "1.7.10-10.13.4.1614-1.7.10"
+fn forge_library_version(dir: &Path) -> Option<(String, String)> {
+    let full = newest_version(
+        sorted_dir_names(&dir.join("libraries/net/minecraftforge/forge")),
+        |name| name.split('-').nth(1).unwrap_or(name),
+    )?;
+    let (mc, build) = full.split_once('-')?;
+    let build = build.strip_suffix(&format!("-{}", mc)).unwrap_or(build);
+
+    Some((mc.to_string(), build.to_string()))
+}
+
+// "libraries/net/neoforged/neoforge/21.1.77" -> ("1.21.1", "21.1.77")
+fn neoforge_library_version(dir: &Path) -> Option<(String, String)> {
+    let build = newest_version(
+        sorted_dir_names(&dir.join("libraries/net/neoforged/neoforge")),
+        |name| name,
+    )?;
+
+    Some((neoforge_mc_version(&build)?, build))
+}
+
+// "forge-1.16.5-36.2.39.jar" or "forge-1.12.2-14.23.5.2860-universal.jar"
+fn forge_jar(jars: &[String]) -> Option<String> {
+    jars.iter()
+        .find(|j| j.starts_with("forge-") && !j.contains("installer"))
+        .cloned()
+}
+
+// ".fabric/remappedJars/minecraft-1.20.1-0.15.11" -> ("1.20.1", "0.15.11")
+fn fabric_remapped_version(dir: &Path) -> Option<(String, String)> {
+    let newest = newest_version(
+        sorted_dir_names(&dir.join(".fabric/remappedJars")),
+        |name| name.rsplit_once('-').map_or(name, |(_, loader)| loader),
+    )?;
+    let (mc, loader) = newest.strip_prefix("minecraft-")?.rsplit_once('-')?;
+
+    Some((mc.to_string(), loader.to_string()))
+}
+
+fn fabric_loader_library(dir: &Path) -> Option<String> {
+    newest_version(
+        sorted_dir_names(&dir.join("libraries/net/fabricmc/fabric-loader")),
+        |name| name,
+    )
+}
+
+/// -Xmx from launch scripts and Forge's user_jvm_args.txt, rounded up to whole GB
+fn script_ram_gb(dir: &Path) -> Option<u8> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return None;
+    };
+
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            name == "user_jvm_args.txt"
+                || name.ends_with(".sh")
+                || name.ends_with(".bat")
+                || name.ends_with(".command")
+        })
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .find_map(|text| {
+            text.split_whitespace().find_map(|arg| {
+                let value = arg.strip_prefix("-Xmx")?;
+                let (digits, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit())?);
+                let amount: u64 = digits.parse().ok()?;
+
+                let gb = match unit.to_ascii_lowercase().as_str() {
+                    "g" => amount,
+                    "m" => amount.div_ceil(1024),
+                    _ => return None,
+                };
+
+                Some(gb.clamp(1, 64) as u8)
+            })
+        })
+}
+
+pub fn default_ram_gb(loader: &LoaderType) -> u8 {
+    match loader {
+        LoaderType::Fabric | LoaderType::Forge | LoaderType::NeoForge => 4,
+        LoaderType::Vanilla | LoaderType::Paper => 2,
+        LoaderType::Bedrock => 1, // not passed to bedrock_server, just shown
+    }
+}
+
+pub fn detect_server(dir: &Path) -> Result<DetectedServer, String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+
+    let jars = root_jars(dir);
+    let has = |name: &str| dir.join(name).exists();
+
+    let mut version = None;
+    let mut loader_build = None;
+    let mut server_jar = None;
+    let mut java_major = None;
+
+    let loader = if has("bedrock_server") || has("bedrock_server.exe") {
+        LoaderType::Bedrock
+    } else if let Some((mc, build)) = neoforge_library_version(dir) {
+        version = Some(mc);
+        loader_build = Some(build);
+        LoaderType::NeoForge
+    } else if let Some((mc, build)) = forge_library_version(dir) {
+        version = Some(mc);
+        loader_build = Some(build);
+        // Newer Forge launches through a shim jar that find_forge_entry picks up
+        server_jar = forge_jar(&jars).filter(|j| !j.contains("shim"));
+        LoaderType::Forge
+    } else if let Some(jar) = forge_jar(&jars) {
+        // forge-<mc>-<build>[-universal].jar
+        let mut parts = jar.trim_end_matches(".jar").splitn(3, '-').skip(1);
+        version = parts.next().map(String::from);
+        loader_build = parts
+            .next()
+            .map(|b| b.trim_end_matches("-universal").to_string());
+        server_jar = Some(jar);
+        LoaderType::Forge
+    } else if has("fabric-server-launch.jar")
+        || has("fabric-server-launcher.properties")
+        || has(".fabric")
+    {
+        if let Some((mc, loader)) = fabric_remapped_version(dir) {
+            version = Some(mc);
+            loader_build = Some(loader);
+        }
+        loader_build = loader_build.or_else(|| fabric_loader_library(dir));
+
+        if has("fabric-server-launch.jar") {
+            server_jar = Some("fabric-server-launch.jar".to_string());
+        }
+        LoaderType::Fabric
+    } else if has("version_history.json")
+        || jars.iter().any(|j| j.to_lowercase().starts_with("paper"))
+    {
+        version = paper_version(dir);
+        server_jar = jars
+            .iter()
+            .find(|j| j.to_lowercase().starts_with("paper"))
+            .filter(|j| j.as_str() != "server.jar")
+            .cloned();
+        LoaderType::Paper
+    } else if !jars.is_empty() {
+        // Prefer server.jar, otherwise the jar carrying a version.json
+        let jar = if jars.iter().any(|j| j == "server.jar") {
+            "server.jar".to_string()
+        } else {
+            jars.iter()
+                .find(|j| jar_version(&dir.join(j)).is_some())
+                .or(jars.iter().find(|j| j.starts_with("minecraft_server.")))
+                .unwrap_or(&jars[0])
+                .clone()
+        };
+
+        // minecraft_server.1.12.2.jar predates version.json
+        version = jar
+            .strip_prefix("minecraft_server.")
+            .and_then(|v| v.strip_suffix(".jar"))
+            .map(String::from);
+
+        if jar != "server.jar" {
+            server_jar = Some(jar);
+        }
+        LoaderType::Vanilla
+    } else {
+        return Err("No Minecraft server found in this directory".into());
+    };
+
+    // The vanilla jar's version.json is the most reliable source where it exists
+    if !matches!(loader, LoaderType::Bedrock) {
+        if let Some((id, java)) = any_jar_version(dir, &jars) {
+            java_major = java;
+            if version.is_none() || matches!(loader, LoaderType::Vanilla) {
+                version = Some(id);
+            }
+        }
+    }
+
+    Ok(DetectedServer {
+        name: dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        ram_gb: script_ram_gb(dir).unwrap_or_else(|| default_ram_gb(&loader)),
+        loader,
+        version,
+        loader_build,
+        server_jar,
+        java_major,
+    })
+}
+
+/// Moving and linking
+
+fn move_dir(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    // rename can't cross file systems
+    copy_dir_all(from, to)?;
+    fs::remove_dir_all(from)
+        .map_err(|e| format!("Copied server but failed to remove the original: {}", e))
+}
+
+fn link_dir(from: &Path, to: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    let linked = std::os::unix::fs::symlink(from, to);
+
+    #[cfg(windows)]
+    let linked = std::os::windows::fs::symlink_dir(from, to);
+
+    linked.map_err(|e| {
+        format!(
+            "Failed to link server directory (try moving it instead): {}",
+            e
+        )
+    })
+}
+
+/// Import Commands
+
+#[tauri::command]
+pub fn inspect_server(path: String) -> Result<DetectedServer, String> {
+    detect_server(Path::new(&path))
+}
+
+/// `name` and `version` override what was detected; a version is required
+/// when it can't be detected, e.g. for Bedrock
+#[tauri::command]
+pub fn import_server(
+    path: String,
+    name: Option<String>,
+    version: Option<String>,
+    mode: Option<ImportMode>,
+) -> Result<ServerConfig, String> {
+    let source = fs::canonicalize(&path).map_err(|e| format!("Cannot open {}: {}", path, e))?;
+
+    if let Ok(base) = fs::canonicalize(servers_dir()) {
+        if source.starts_with(base) {
+            return Err("This server is already managed by Cubely".into());
+        }
+    }
+
+    if source.join("cubely.json").exists() {
+        return Err("This directory already has a cubely.json".into());
+    }
+
+    let detected = detect_server(&source)?;
+
+    let name = validate_server_name(name.as_deref().unwrap_or(&detected.name))?;
+    ensure_name_available(&name, None)?;
+
+    let version = version
+        .or(detected.version)
+        .ok_or("Couldn't detect the Minecraft version, please pick it")?;
+
+    let target = unique_server_dir(&name, None);
+    fs::create_dir_all(servers_dir()).map_err(|e| e.to_string())?;
+
+    match mode.unwrap_or_default() {
+        ImportMode::Link => link_dir(&source, &target)?,
+        ImportMode::Move => move_dir(&source, &target)?,
+    }
+
+    let config = ServerConfig {
+        id: Uuid::new_v4().to_string(),
+        name,
+        version,
+        loader: detected.loader,
+        ram_gb: detected.ram_gb,
+        path: target.to_string_lossy().to_string(),
+        created_at: Utc::now().timestamp(),
+        java_major: detected.java_major,
+        loader_build: detected.loader_build,
+        installer_version: None,
+        server_jar: detected.server_jar,
+        tunnel: Some(TunnelConfig {
+            enabled: false,
+            provider: TunnelProvider::Playit,
+        }),
+        crossplay: None,
+        block_on_mod_errors: false,
+    };
+
+    save_server_config(&config)?;
+
+    Ok(config)
+}
@@ -1,15 +1,28 @@
 use std::io::BufRead;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 use std::{fs, path::PathBuf, process::Command};
 
 use playit_api_client::PlayitApi;
 use serde::{Deserialize, Serialize};
 use tauri::Emitter;
 
-use crate::commands::java_manager::{install_java, java_binary, java_installed, require_java};
+use crate::commands::crossplay_manager::{
+    start_geyser_standalone, write_geyser_config, CrossplayConfig, CrossplayMode,
+};
+use crate::commands::mod_bisect::record_bisect_launch;
+use crate::commands::mod_checker::check_mods;
+use crate::commands::mod_updates::{confirm_mod_update, rollback_mod_update, PENDING_FILE};
+use crate::commands::java_manager::{ensure_java, server_java, JavaVersion};
 use crate::commands::ngrok_manager::{install_ngrok, ngrok_binary, ngrok_installed, start_ngrok};
 use crate::commands::playit_manager::{get_playit_public_url, install_playit, playit_binary, playit_installed, start_playit};
+use crate::utils::download::DownloadEvents;
 use crate::{
-    commands::server_creation::LoaderType, state::app_state::AppState, utils::path::servers_dir,
+    commands::server_creation::LoaderType,
+    state::app_state::AppState,
+    utils::path::{cleanup_empty_parent_dir, servers_dir, unique_server_dir},
 };
 
 /// READING AND WRITING OF SERVERS
@@ -27,6 +40,22 @@ pub struct TunnelConfig {
     pub provider: TunnelProvider, // "playit"
 }
 
+/// Transport a tunnel has to forward: Java Edition speaks TCP, Bedrock speaks UDP (RakNet)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TunnelProtocol {
+    Tcp,
+    Udp,
+}
+
+impl TunnelProtocol {
+    pub fn for_loader(loader: &LoaderType) -> Self {
+        match loader {
+            LoaderType::Bedrock => TunnelProtocol::Udp,
+            _ => TunnelProtocol::Tcp,
+        }
+    }
+}
+
 impl Default for TunnelConfig {
     fn default() -> Self {
         Self {
@@ -46,8 +75,31 @@ pub struct ServerConfig {
     pub path: String,
     pub created_at: i64,
 
+    // Java major version from Mojang's version metadata, cached at creation
+    #[serde(default)]
+    pub java_major: Option<u32>,
+
+    // Loader build chosen at creation, the Fabric loader "0.16.10" or Forge build "47.2.0"
+    #[serde(default)]
+    pub loader_build: Option<String>,
+
+    // Fabric installer that set the server up, "1.0.1"
+    #[serde(default)]
+    pub installer_version: Option<String>,
+
+    // Jar to launch when it isn't the loader's usual one, set for imported servers
+    #[serde(default)]
+    pub server_jar: Option<String>,
+
     #[serde(default)]
     pub tunnel: Option<TunnelConfig>,
+
+    #[serde(default)]
+    pub crossplay: Option<CrossplayConfig>,
+
+    // Refuse to start while the mod check finds errors, instead of only reporting them
+    #[serde(default)]
+    pub block_on_mod_errors: bool,
 }
 
 #[tauri::command]
@@ -60,26 +112,127 @@ pub fn list_servers() -> Result<Vec<ServerConfig>, String> {
         return Ok(servers);
     }
 
-    for version_dir in fs::read_dir(base).map_err(|e| e.to_string())? {
-        let version_dir = version_dir.map_err(|e| e.to_string())?;
-        if !version_dir.path().is_dir() {
+    // servers/<slug>/cubely.json
+    for server_dir in fs::read_dir(base).map_err(|e| e.to_string())? {
+        let server_dir = server_dir.map_err(|e| e.to_string())?;
+        let config_path = server_dir.path().join("cubely.json");
+
+        if config_path.exists() {
+            let content = fs::read_to_string(config_path).map_err(|e| e.to_string())?;
+            let config: ServerConfig =
+                serde_json::from_str(&content).map_err(|e| e.to_string())?;
+            servers.push(config);
+        }
+    }
+
+    Ok(servers)
+}
+
+pub fn find_server(server_id: &str) -> Result<ServerConfig, String> {
+    list_servers()?
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or("Server not found".into())
+}
+
+const MAX_NAME_LEN: usize = 64;
+
+/// Checks a display name and returns it trimmed
+pub fn validate_server_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err("Server name can't be empty".into());
+    }
+
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("Server name can't be longer than {} characters", MAX_NAME_LEN));
+    }
+
+    if name.chars().any(|c| c.is_control()) {
+        return Err("Server name can't contain control characters".into());
+    }
+
+    Ok(name.to_string())
+}
+
+/// Rejects names another server already uses, ignoring case
+pub fn ensure_name_available(name: &str, except_id: Option<&str>) -> Result<(), String> {
+    let taken = list_servers()?
+        .iter()
+        .any(|s| Some(s.id.as_str()) != except_id && s.name.eq_ignore_ascii_case(name));
+
+    if taken {
+        return Err(format!("A server named {} already exists", name));
+    }
+
+    Ok(())
+}
+
+/// Renames a stopped server, moving its directory to the slug of the new name
+pub fn rename_server_dir(server: &mut ServerConfig, new_name: &str) -> Result<(), String> {
+    let name = validate_server_name(new_name)?;
+    ensure_name_available(&name, Some(&server.id))?;
+
+    let current = PathBuf::from(&server.path);
+    let target = unique_server_dir(&name, Some(&current));
+
+    if target != current {
+        fs::rename(&current, &target)
+            .map_err(|e| format!("Failed to move server to {}: {}", target.display(), e))?;
+        server.path = target.to_string_lossy().to_string();
+    }
+
+    server.name = name;
+    save_server_config(server)
+}
+
+/// Moves servers from the old servers/<version>/<name> layout to servers/<slug>.
+/// Runs at startup; a server that can't be moved is left where it was.
+pub fn migrate_server_layout() {
+    let base = servers_dir();
+    let Ok(version_dirs) = fs::read_dir(&base) else {
+        return;
+    };
+
+    for version_dir in version_dirs.flatten() {
+        let version_path = version_dir.path();
+
+        // Already a server directory in the new layout
+        if !version_path.is_dir() || version_path.join("cubely.json").exists() {
+            continue;
+        }
+
+        let Ok(server_dirs) = fs::read_dir(&version_path) else {
             continue;
-        } // continue if its not a folder and is a file
+        };
 
-        for server_dir in fs::read_dir(version_dir.path()).map_err(|e| e.to_string())? {
-            let server_dir = server_dir.map_err(|e| e.to_string())?;
-            let config_path = server_dir.path().join("cubely.json");
+        for server_dir in server_dirs.flatten() {
+            let old_path = server_dir.path();
+            let config_path = old_path.join("cubely.json");
 
-            if config_path.exists() {
-                let content = fs::read_to_string(config_path).map_err(|e| e.to_string())?;
-                let config: ServerConfig =
-                    serde_json::from_str(&content).map_err(|e| e.to_string())?;
-                servers.push(config);
+            let Some(mut config) = fs::read_to_string(&config_path)
+                .ok()
+                .and_then(|raw| serde_json::from_str::<ServerConfig>(&raw).ok())
+            else {
+                continue;
+            };
+
+            let new_path = unique_server_dir(&config.name, None);
+
+            if let Err(e) = fs::rename(&old_path, &new_path) {
+                eprintln!("Failed to migrate server {:?}: {}", old_path, e);
+                continue;
+            }
+
+            config.path = new_path.to_string_lossy().to_string();
+            if let Err(e) = save_server_config(&config) {
+                eprintln!("Failed to update migrated server {:?}: {}", new_path, e);
             }
         }
-    }
 
-    Ok(servers)
+        cleanup_empty_parent_dir(&version_path);
+    }
 }
 
 use serde_json::Value;
@@ -181,7 +334,14 @@ pub async fn update_server_properties(
     );
     map.insert("server-port".into(), props.server_port.to_string());
 
-    //  Write back EVERYTHING (including unknown keys)
+    write_server_properties(&server_path, &map)
+}
+
+//  Write back EVERYTHING (including unknown keys)
+pub fn write_server_properties(
+    server_path: &String,
+    map: &HashMap<String, String>,
+) -> Result<(), String> {
     let mut output = String::from("# Generated / Updated by Cubely\n");
 
     for (k, v) in map.iter() {
@@ -197,6 +357,110 @@ pub async fn update_server_properties(
     Ok(())
 }
 
+/// READING AND WRITING BEDROCK server.properties
+
+#[derive(Serialize, Deserialize)]
+pub struct BedrockServerProperties {
+    pub server_name: String,
+    pub gamemode: String,
+    pub difficulty: String,
+    pub allow_cheats: bool,
+    pub max_players: u32,
+    pub online_mode: bool,
+    pub allow_list: bool,
+    pub server_port: u16,
+    pub server_portv6: u16,
+    pub view_distance: u32,
+    pub tick_distance: u32,
+    pub level_name: String,
+    pub level_seed: String,
+    pub default_player_permission_level: String,
+}
+
+#[tauri::command]
+pub fn read_bedrock_server_properties(
+    server_path: String,
+) -> Result<BedrockServerProperties, String> {
+    let map = map_server_properties(&server_path)?;
+
+    Ok(BedrockServerProperties {
+        server_name: map.get("server-name").cloned().unwrap_or_default(),
+        gamemode: map.get("gamemode").cloned().unwrap_or("survival".into()),
+        difficulty: map.get("difficulty").cloned().unwrap_or("easy".into()),
+        allow_cheats: map.get("allow-cheats").map(|v| v == "true").unwrap_or(false),
+        max_players: map
+            .get("max-players")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10),
+        online_mode: map.get("online-mode").map(|v| v == "true").unwrap_or(true),
+        allow_list: map.get("allow-list").map(|v| v == "true").unwrap_or(false),
+        server_port: map
+            .get("server-port")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(19132),
+        server_portv6: map
+            .get("server-portv6")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(19133),
+        view_distance: map
+            .get("view-distance")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(32),
+        tick_distance: map
+            .get("tick-distance")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(4),
+        level_name: map.get("level-name").cloned().unwrap_or("Bedrock level".into()),
+        level_seed: map.get("level-seed").cloned().unwrap_or_default(),
+        default_player_permission_level: map
+            .get("default-player-permission-level")
+            .cloned()
+            .unwrap_or("member".into()),
+    })
+}
+
+#[tauri::command]
+pub async fn update_bedrock_server_properties(
+    server_path: String,
+    props: BedrockServerProperties,
+) -> Result<(), String> {
+    let mut map = map_server_properties(&server_path)?;
+
+    // Update only keys we control
+    map.insert("server-name".into(), props.server_name);
+    map.insert("gamemode".into(), props.gamemode);
+    map.insert("difficulty".into(), props.difficulty);
+    map.insert("allow-cheats".into(), props.allow_cheats.to_string());
+    map.insert("max-players".into(), props.max_players.to_string());
+    map.insert("online-mode".into(), props.online_mode.to_string());
+    map.insert("allow-list".into(), props.allow_list.to_string());
+    map.insert("server-port".into(), props.server_port.to_string());
+    map.insert("server-portv6".into(), props.server_portv6.to_string());
+    map.insert("view-distance".into(), props.view_distance.to_string());
+    map.insert("tick-distance".into(), props.tick_distance.to_string());
+    map.insert("level-name".into(), props.level_name);
+    map.insert("level-seed".into(), props.level_seed);
+    map.insert(
+        "default-player-permission-level".into(),
+        props.default_player_permission_level,
+    );
+
+    write_server_properties(&server_path, &map)
+}
+
+// Port the server listens on, as configured in server.properties
+pub fn server_port(server: &ServerConfig) -> u16 {
+    let default = match server.loader {
+        LoaderType::Bedrock => 19132,
+        _ => 25565,
+    };
+
+    map_server_properties(&server.path)
+        .ok()
+        .and_then(|map| map.get("server-port").and_then(|v| v.parse().ok()))
+        .unwrap_or(default)
+}
+
 /// READING AND WRITING SERVER CONFIG (cubely.json)
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -206,6 +470,9 @@ pub struct EditableServerConfig {
 
     #[serde(default)]
     pub tunnel: TunnelConfig,
+
+    #[serde(default)]
+    pub block_on_mod_errors: bool,
 }
 
 impl Default for EditableServerConfig {
@@ -214,6 +481,7 @@ impl Default for EditableServerConfig {
             name: String::new(),
             ram_gb: 2,
             tunnel: TunnelConfig::default(),
+            block_on_mod_errors: false,
         }
     }
 }
@@ -229,27 +497,68 @@ pub fn read_server_config(server_path: String) -> Result<EditableServerConfig, S
         name: full.name.clone(),
         ram_gb: full.ram_gb,
         tunnel: full.tunnel.unwrap_or(TunnelConfig::default()),
+        block_on_mod_errors: full.block_on_mod_errors,
     })
 }
 
+pub fn save_server_config(server: &ServerConfig) -> Result<(), String> {
+    fs::write(
+        PathBuf::from(&server.path).join("cubely.json"),
+        serde_json::to_string_pretty(server).unwrap(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the updated config, whose path changes when the name does
 #[tauri::command]
 pub fn update_server_config(
     server_path: String,
     props: EditableServerConfig,
-) -> Result<(), String> {
+    state: tauri::State<'_, AppState>,
+) -> Result<ServerConfig, String> {
     let path = PathBuf::from(server_path).join("cubely.json");
 
     let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
     let mut full: ServerConfig = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
 
     // Only allow safe fields
-    full.name = props.name;
     full.ram_gb = props.ram_gb;
     full.tunnel = Some(props.tunnel);
+    full.block_on_mod_errors = props.block_on_mod_errors;
 
-    fs::write(&path, serde_json::to_string_pretty(&full).unwrap()).map_err(|e| e.to_string())?;
+    if props.name.trim() != full.name {
+        ensure_stopped(&full.id, &state, "Stop the server before renaming it")?;
+        rename_server_dir(&mut full, &props.name)?;
+    } else {
+        save_server_config(&full)?;
+    }
 
-    Ok(())
+    Ok(full)
+}
+
+#[tauri::command]
+pub fn rename_server(
+    server_id: String,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ServerConfig, String> {
+    ensure_stopped(&server_id, &state, "Stop the server before renaming it")?;
+
+    let mut server = find_server(&server_id)?;
+
+    rename_server_dir(&mut server, &name)?;
+
+    Ok(server)
+}
+
+/// Errors with `message` while `server_id` is the running server
+pub fn ensure_stopped(server_id: &str, state: &AppState, message: &str) -> Result<(), String> {
+    let active = state.active_server.lock().unwrap();
+
+    match active.as_ref() {
+        Some(active) if active.server_id == server_id => Err(message.into()),
+        _ => Ok(()),
+    }
 }
 
 /// SERVER STARTUP AND STOPPING
@@ -261,7 +570,9 @@ pub struct ActiveServer {
     pub mc_child: Child,
     pub ngrok_child: Option<Child>,
     pub playit_child: Option<Child>,
+    pub geyser_child: Option<Child>,
     pub public_url: Option<String>,
+    pub bedrock_public_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -269,6 +580,7 @@ pub struct ActiveServerInfo {
     pub server_name: String,
     pub server_id: String,
     pub public_url: Option<String>,
+    pub bedrock_public_url: Option<String>,
 }
 
 #[tauri::command]
@@ -279,6 +591,7 @@ pub fn get_active_server(state: tauri::State<'_, AppState>) -> Option<ActiveServ
         server_name: s.server_name.clone(),
         server_id: s.server_id.clone(),
         public_url: s.public_url.clone(),
+        bedrock_public_url: s.bedrock_public_url.clone(),
     })
 }
 
@@ -296,31 +609,50 @@ pub async fn start_server(
         }
     } // <- mutex guard DROPPED here
 
-    // Check and install if required java version is missing
-    let java_version = require_java(&server.version);
-
-    // lock once
-    let java_base = {
-        let guard = state.java_base_dir.lock().unwrap();
+    let app = {
+        let guard = state.app_handle.lock().unwrap();
         guard
             .clone()
-            .ok_or("Java base directory not initialized")?
+            .ok_or("App handle not initialized")?
+    };
+
+    // Loaders fail late and cryptically on a broken mod set, so look first.
+    // Opening every jar is slow on big packs, keep it off the async runtime
+    let mod_report = {
+        let server = server.clone();
+        tauri::async_runtime::spawn_blocking(move || check_mods(&server))
+            .await
+            .map_err(|e| format!("Mod check failed: {}", e))?
     };
 
-    if !java_installed(&java_base, java_version) {
-        install_java(&java_base, java_version).await?;
+    if mod_report.has_errors && server.block_on_mod_errors {
+        return Err(format!("Mod check failed: {}", mod_report.summary()));
+    }
+
+    if !mod_report.issues.is_empty() {
+        let _ = app.emit("mod-check", &mod_report);
     }
 
-    let java = java_binary(&java_base, java_version);
+    // Check and install if required java version is missing (Bedrock runs natively)
+    let java = if let LoaderType::Bedrock = server.loader {
+        None
+    } else {
+        let java_version = server_java(server.java_major, &server.version);
 
-    // println!("Java path: {}", java.display());
-    // println!("Java exists: {}", java.exists());
-    // println!("Server path: {}", server.path);
-    // println!("Server path exists: {}", PathBuf::from(&server.path).exists());
+        // lock once
+        let java_base = {
+            let guard = state.java_base_dir.lock().unwrap();
+            guard
+                .clone()
+                .ok_or("Java base directory not initialized")?
+        };
 
-    if !java.exists() {
-        return Err(format!("Java not found at {}", java.display()));
-    }
+        // println!("Server path: {}", server.path);
+        // println!("Server path exists: {}", PathBuf::from(&server.path).exists());
+
+        let progress = DownloadEvents::new(app.clone(), format!("Java {}", java_version.major()));
+        Some(ensure_java(&java_base, java_version, &progress).await?)
+    };
 
     let ngrok_base = {
         let guard = state.ngrok_base_dir.lock().unwrap();
@@ -340,17 +672,19 @@ pub async fn start_server(
         return Err(format!("Server directory not found: {}", server.path));
     }
 
+    // Geyser reads its config on startup, so point it at the current server-port first
+    let crossplay = server.crossplay.clone().filter(|c| c.enabled);
+
+    if let Some(crossplay) = &crossplay {
+        write_geyser_config(&server, crossplay)?;
+    }
+
     // spawn minecraft
-    let mut mc_child: Child = match server.loader {
-        LoaderType::Vanilla | LoaderType::Fabric => {
-            Command::new(java)
-                .args([
-                    format!("-Xmx{}G", server.ram_gb),
-                    format!("-Xms{}G", server.ram_gb),
-                    "-jar".into(),
-                    "server.jar".into(),
-                    "nogui".into(),
-                ])
+    let mut mc_child: Child = match (&server.loader, java) {
+        (LoaderType::Bedrock, _) => {
+            // bedrock_server ships its own shared libraries next to the binary
+            Command::new(PathBuf::from(&server.path).join("bedrock_server"))
+                .env("LD_LIBRARY_PATH", ".")
                 .current_dir(&server.path)
                 .stdin(Stdio::piped())
                 .stdout(Stdio::piped())
@@ -359,42 +693,66 @@ pub async fn start_server(
                 .map_err(|e| e.to_string())?
         }
 
-        LoaderType::Forge => {
-            let jar_name = find_forge_entry(&server.path)?;
-
-            Command::new(java)
-                .args([
-                    format!("-Xmx{}G", server.ram_gb),
-                    format!("-Xms{}G", server.ram_gb),
-                    "-jar".into(),
-                    jar_name,
-                    "nogui".into(),
-                ])
-                .current_dir(&server.path)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .map_err(|e| e.to_string())?
-        }
+        (_, None) => return Err("Java runtime not resolved".into()),
+
+        (_, Some(java)) => java_server_command(&server, &java)?
+            .spawn()
+            .map_err(|e| e.to_string())?,
     };
 
     // Logging to frontend
-    let app = {
-        let guard = state.app_handle.lock().unwrap();
-        guard
-            .clone()
-            .ok_or("App handle not initialized")?
-    };
+    let _ = app.emit("server-status", ServerStatus::Starting);
+
+    // Set when Cubely kills the server itself, that exit says nothing about the mods
+    let aborted = Arc::new(AtomicBool::new(false));
 
     if let Some(stdout) = mc_child.stdout.take() {
         let app = app.clone();
+        let aborted = aborted.clone();
+        let loader = server.loader.clone();
+        let server_path = PathBuf::from(&server.path);
+        let bisected = server.clone();
 
         std::thread::spawn(move || {
+            let mut started = false;
+            let mut stopping = false;
+            let mut crash_report = false;
+
             let reader = std::io::BufReader::new(stdout);
             for line in reader.lines().flatten() {
+                crash_report |= is_crash_report_line(&line);
+
+                if let Some(status) = parse_status_line(&loader, &line) {
+                    if status == ServerStatus::Running && !started {
+                        started = true;
+                        confirm_mod_update(&server_path);
+                    }
+
+                    stopping |= status == ServerStatus::Stopping;
+                    let _ = app.emit("server-status", status);
+                }
+
                 let _ = app.emit("mc-log", line);
             }
+
+            // Died before it was up, the last mod update is the likely cause.
+            // A stop sent while starting is only handled after "Done".
+            let judged = !aborted.load(Ordering::SeqCst);
+
+            if judged && !started && !stopping && server_path.join(PENDING_FILE).exists() {
+                let rollback = rollback_mod_update(&server_path);
+                let _ = app.emit("mod-update-rollback", rollback);
+            }
+
+            // Stopped before it was up says nothing either way, same as above
+            if judged && (started || !stopping) {
+                if let Some(bisect) = record_bisect_launch(&bisected, !started || crash_report) {
+                    let _ = app.emit("mod-bisect", bisect);
+                }
+            }
+
+            // stdout closes when the process exits
+            let _ = app.emit("server-status", ServerStatus::Stopped);
         });
     }
 
@@ -409,75 +767,176 @@ pub async fn start_server(
         });
     }
 
+    let mut geyser_child = None;
     let mut ngrok_child = None;
     let mut playit_child = None;
     let mut public_url = None;
+    let mut bedrock_public_url = None;
+
+    let port = server_port(&server);
+    let protocol = TunnelProtocol::for_loader(&server.loader);
+
+    // The server is already running, a failure below must not leave it orphaned
+    let setup = async {
+        // Standalone Geyser runs as its own process in front of the server
+        if let Some(CrossplayConfig { mode: CrossplayMode::Standalone, .. }) = &crossplay {
+            let java_base = {
+                let guard = state.java_base_dir.lock().unwrap();
+                guard
+                    .clone()
+                    .ok_or("Java base directory not initialized")?
+            };
+
+            let progress = DownloadEvents::new(app.clone(), "Java 21");
+            let java = ensure_java(&java_base, JavaVersion(21), &progress).await?;
+            let mut child = start_geyser_standalone(&java, &server.path)?;
+
+            if let Some(stdout) = child.stdout.take() {
+                let app = app.clone();
+
+                std::thread::spawn(move || {
+                    let reader = std::io::BufReader::new(stdout);
+                    for line in reader.lines().flatten() {
+                        let _ = app.emit("mc-log", format!("[GEYSER] {}", line));
+                    }
+                });
+            }
 
-    if let Some(tunnel) = &server.tunnel {
-        if tunnel.enabled {
-            match tunnel.provider {
-                TunnelProvider::Playit => {
-                    if !playit_installed(&playit_base) {
-                        install_playit(&playit_base).await?;
+            if let Some(stderr) = child.stderr.take() {
+                let app = app.clone();
+
+                std::thread::spawn(move || {
+                    let reader = std::io::BufReader::new(stderr);
+                    for line in reader.lines().flatten() {
+                        let _ = app.emit("mc-log", format!("[GEYSER][ERR] {}", line));
                     }
+                });
+            }
 
-                    let child = start_playit(&playit_base)?;
-                    playit_child = Some(child);
+            geyser_child = Some(child);
+        }
 
-                    if let Some(child) = playit_child.as_mut() {
-                        if let Some(stdout) = child.stdout.take() {
-                            let app = app.clone();
 
-                            std::thread::spawn(move || {
-                                let reader = std::io::BufReader::new(stdout);
-                                for line in reader.lines().flatten() {
-                                    let _ = app.emit("playit-log", line);
-                                }
-                            });
+        if let Some(tunnel) = &server.tunnel {
+            if tunnel.enabled {
+                match tunnel.provider {
+                    TunnelProvider::Playit => {
+                        if !playit_installed(&playit_base) {
+                            install_playit(&playit_base, &DownloadEvents::new(app.clone(), "Playit"))
+                                .await?;
                         }
 
-                        if let Some(stderr) = child.stderr.take() {
-                            let app = app.clone();
+                        let child = start_playit(&playit_base)?;
+                        playit_child = Some(child);
+
+                        if let Some(child) = playit_child.as_mut() {
+                            if let Some(stdout) = child.stdout.take() {
+                                let app = app.clone();
+
+                                std::thread::spawn(move || {
+                                    let reader = std::io::BufReader::new(stdout);
+                                    for line in reader.lines().flatten() {
+                                        let _ = app.emit("playit-log", line);
+                                    }
+                                });
+                            }
+
+                            if let Some(stderr) = child.stderr.take() {
+                                let app = app.clone();
+
+                                std::thread::spawn(move || {
+                                    let reader = std::io::BufReader::new(stderr);
+                                    for line in reader.lines().flatten() {
+                                        let _  = app.emit("playit-log", format!("[ERR] {}", line));
+                                    }
+                                });
+                            }
+                        }
 
-                            std::thread::spawn(move || {
-                                let reader = std::io::BufReader::new(stderr);
-                                for line in reader.lines().flatten() {
-                                    let _  = app.emit("playit-log", format!("[ERR] {}", line));
+                        let url = get_playit_public_url(app.clone(), port, protocol).await?;
+
+                        let _ = app.emit(
+                            "playit-log",
+                            format!("[PLAYIT] public url: {}", url),
+                        );
+
+                        public_url = Some(url);
+
+                        // Bedrock players reach Geyser through a separate UDP tunnel
+                        if let Some(crossplay) = &crossplay {
+                            match get_playit_public_url(
+                                app.clone(),
+                                crossplay.bedrock_port,
+                                TunnelProtocol::Udp,
+                            )
+                            .await
+                            {
+                                Ok(url) => {
+                                    let _ = app.emit(
+                                        "playit-log",
+                                        format!("[PLAYIT] bedrock url: {}", url),
+                                    );
+
+                                    bedrock_public_url = Some(url);
                                 }
-                            });
+                                Err(e) => {
+                                    let _ = app.emit("playit-log", format!("[PLAYIT] {}", e));
+                                }
+                            }
                         }
                     }
 
-                    let url = get_playit_public_url(app.clone()).await?;
-
-                    let _ = app.emit(
-                        "playit-log",
-                        format!("[PLAYIT] public url: {}", url),
-                    );
-
-                    public_url = Some(url);
-                }
+                    TunnelProvider::Ngrok => {
+                        if !ngrok_installed(&ngrok_base) {
+                            install_ngrok(&ngrok_base, &DownloadEvents::new(app.clone(), "Ngrok"))
+                                .await?;
+                        }
 
-                TunnelProvider::Ngrok => {
-                    if !ngrok_installed(&ngrok_base) {
-                        install_ngrok(&ngrok_base).await?;
+                        // Async rule: never hold std::sync::MutexGuard across .await
+                        // The future must be Send (Tauri requirement)
+                        // Drop active_server mutex before awaiting — MutexGuard is not Send
+                        let (child, url) = start_ngrok(port, protocol, &ngrok_base).await?;
+                        ngrok_child = Some(child);
+                        public_url = Some(url);
+
+                        if crossplay.is_some() {
+                            let _ = app.emit(
+                                "mc-log",
+                                "[CUBELY] Ngrok cannot forward UDP, Bedrock players can only join over LAN. Use Playit for crossplay.",
+                            );
+                        }
                     }
-
-                    // Async rule: never hold std::sync::MutexGuard across .await
-                    // The future must be Send (Tauri requirement)
-                    // Drop active_server mutex before awaiting — MutexGuard is not Send
-                    let (child, url) = start_ngrok(25565, &ngrok_base).await?;
-                    ngrok_child = Some(child);
-                    public_url = Some(url);
                 }
             }
         }
+
+        Ok::<(), String>(())
+    }
+    .await;
+
+    if let Err(e) = setup {
+        aborted.store(true, Ordering::SeqCst);
+
+        let children = [
+            Some(&mut mc_child),
+            geyser_child.as_mut(),
+            playit_child.as_mut(),
+            ngrok_child.as_mut(),
+        ];
+
+        for child in children.into_iter().flatten() {
+            child.kill().ok();
+            child.wait().ok();
+        }
+
+        return Err(e);
     }
 
     let info = ActiveServerInfo {
         server_name: server.name.clone(),
         server_id: server.id.clone(),
         public_url: public_url.clone(),
+        bedrock_public_url: bedrock_public_url.clone(),
     };
 
     let mut active = state.active_server.lock().unwrap();
@@ -487,12 +946,93 @@ pub async fn start_server(
         mc_child,
         ngrok_child,
         playit_child,
+        geyser_child,
         public_url,
+        bedrock_public_url,
     });
 
     Ok(info)
 }
 
+/// SERVER STATUS (derived from console output)
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerStatus {
+    Starting,
+    Running,
+    Stopping,
+    Stopped,
+}
+
+/// Maps a console line to the status it announces, if any.
+///
+/// Java:    [12:00:00] [Server thread/INFO]: Done (3.2s)! For help, type "help"
+/// Bedrock: [2024-05-01 12:00:00:123 INFO] Server started.
+pub fn parse_status_line(loader: &LoaderType, line: &str) -> Option<ServerStatus> {
+    match loader {
+        LoaderType::Bedrock => {
+            let message = line.split_once("] ").map(|(_, m)| m).unwrap_or(line).trim();
+
+            if message.starts_with("Server started.") {
+                Some(ServerStatus::Running)
+            } else if message.starts_with("Stopping server") {
+                Some(ServerStatus::Stopping)
+            } else if message.starts_with("Quit correctly") {
+                Some(ServerStatus::Stopped)
+            } else {
+                None
+            }
+        }
+
+        _ => {
+            let message = line.split_once("]: ").map(|(_, m)| m).unwrap_or(line).trim();
+
+            if message.starts_with("Done (") {
+                Some(ServerStatus::Running)
+            } else if message.starts_with("Stopping server") {
+                Some(ServerStatus::Stopping)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Whether a console line reports a crash, which a server can also log after "Done"
+///
+/// Vanilla and Fabric: This crash report has been saved to: /srv/crash-reports/crash-....txt
+/// Forge:              Crash report saved to: ./crash-reports/crash-....txt
+pub fn is_crash_report_line(line: &str) -> bool {
+    line.contains("crash report has been saved to") || line.contains("Crash report saved to")
+}
+
+/// Command launching a Java server with piped stdio
+pub fn java_server_command(server: &ServerConfig, java: &PathBuf) -> Result<Command, String> {
+    let launch = match (&server.loader, &server.server_jar) {
+        (LoaderType::Bedrock, _) => return Err("Bedrock servers don't run on Java".into()),
+        (_, Some(jar)) => vec!["-jar".to_string(), jar.clone()],
+        (LoaderType::Vanilla | LoaderType::Fabric | LoaderType::Paper, None) => {
+            vec!["-jar".to_string(), "server.jar".to_string()]
+        }
+        (LoaderType::Forge, None) => vec!["-jar".to_string(), find_forge_entry(&server.path)?],
+        (LoaderType::NeoForge, None) => vec![find_neoforge_args(server)?],
+    };
+
+    let mut command = Command::new(java);
+    command
+        .arg(format!("-Xmx{}G", server.ram_gb))
+        .arg(format!("-Xms{}G", server.ram_gb))
+        .args(launch)
+        .arg("nogui")
+        .current_dir(&server.path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    Ok(command)
+}
+
 fn find_forge_entry(server_path: &str) -> Result<String, String> {
     let dir = PathBuf::from(server_path);
 
@@ -516,6 +1056,29 @@ fn find_forge_entry(server_path: &str) -> Result<String, String> {
     Err("Could not find Forge launch jar".into())
 }
 
+/// NeoForge starts from the argument file its installer writes, e.g.
+/// `@libraries/net/neoforged/neoforge/21.1.77/unix_args.txt`
+fn find_neoforge_args(server: &ServerConfig) -> Result<String, String> {
+    let file = if cfg!(target_os = "windows") { "win_args.txt" } else { "unix_args.txt" };
+    let base = PathBuf::from(&server.path).join("libraries/net/neoforged/neoforge");
+
+    let build = match &server.loader_build {
+        Some(build) => build.clone(),
+        None => fs::read_dir(&base)
+            .map_err(|_| "Could not find NeoForge libraries".to_string())?
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .find(|b| base.join(b).join(file).exists())
+            .ok_or("Could not find NeoForge launch arguments")?,
+    };
+
+    if !base.join(&build).join(file).exists() {
+        return Err(format!("NeoForge {} is not installed", build));
+    }
+
+    Ok(format!("@libraries/net/neoforged/neoforge/{}/{}", build, file))
+}
+
 #[tauri::command]
 pub fn stop_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
     let mut active = state.active_server.lock().unwrap();
@@ -529,6 +1092,10 @@ pub fn stop_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
         // wait for clean shutdown
         server.mc_child.wait().ok();
 
+        if let Some(mut geyser) = server.geyser_child {
+            geyser.kill().ok();
+        }
+
         if let Some(mut playit) = server.playit_child {
             playit.kill().ok();
         }
@@ -0,0 +1,249 @@
+use std::{fs, path::PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::commands::creation_jobs::{CreatePhase, CreationJob};
+use crate::commands::crossplay_manager::CrossplayConfig;
+use crate::commands::mod_bisect::BISECT_FILE;
+use crate::commands::mod_updates::PENDING_FILE;
+use crate::commands::server_creation::{InstalledServer, LoaderType};
+use crate::commands::server_management::{
+    ensure_name_available, ensure_stopped, find_server, map_server_properties, save_server_config,
+    validate_server_name, ServerConfig,
+};
+use crate::state::app_state::AppState;
+use crate::utils::path::{cleanup_server_dir, copy_dir_excluding, templates_dir, unique_server_dir};
+
+/// CLONING AND TEMPLATES
+///
+/// A clone is a full copy of a server under a new id, optionally without its
+/// world. A template is a server minus world and logs, saved under
+/// `templates_dir()` so `create_server` can stamp out new servers from it
+/// without downloading anything.
+
+const TEMPLATE_FILE: &str = "template.json";
+
+// Per-run files that never belong in a copy, a pending mod update points at
+// the source server's backups and a bisect belongs to the source's launches
+const RUNTIME_ENTRIES: &[&str] = &[
+    "logs",
+    "crash-reports",
+    "debug",
+    "backups",
+    "cubely.json",
+    PENDING_FILE,
+    BISECT_FILE,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerTemplate {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub loader: LoaderType,
+    pub ram_gb: u8,
+    pub created_at: i64,
+
+    #[serde(default)]
+    pub java_major: Option<u32>,
+
+    #[serde(default)]
+    pub loader_build: Option<String>,
+
+    #[serde(default)]
+    pub installer_version: Option<String>,
+
+    #[serde(default)]
+    pub server_jar: Option<String>,
+
+    #[serde(default)]
+    pub crossplay: Option<CrossplayConfig>,
+}
+
+/// Top-level entries left out of a copy, the world folders only when `include_world` is false
+fn excluded_entries(server: &ServerConfig, include_world: bool) -> Vec<String> {
+    let mut exclude: Vec<String> = RUNTIME_ENTRIES.iter().map(|e| e.to_string()).collect();
+
+    if include_world {
+        return exclude;
+    }
+
+    if let LoaderType::Bedrock = server.loader {
+        exclude.push("worlds".into());
+    } else {
+        let level = map_server_properties(&server.path)
+            .ok()
+            .and_then(|props| props.get("level-name").cloned())
+            .unwrap_or("world".into());
+
+        // Paper keeps the other dimensions in their own folders
+        exclude.push(format!("{}_nether", level));
+        exclude.push(format!("{}_the_end", level));
+        exclude.push(level);
+    }
+
+    exclude
+}
+
+/// Cloning
+
+#[tauri::command]
+pub fn clone_server(
+    server_id: String,
+    new_name: String,
+    include_world: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ServerConfig, String> {
+    // A running server would be copied with torn region files
+    ensure_stopped(&server_id, &state, "Stop the server before cloning it")?;
+
+    let source = find_server(&server_id)?;
+
+    let name = validate_server_name(&new_name)?;
+    ensure_name_available(&name, None)?;
+
+    let target = unique_server_dir(&name, None);
+    let exclude = excluded_entries(&source, include_world);
+
+    if let Err(e) = copy_dir_excluding(&PathBuf::from(&source.path), &target, &exclude) {
+        cleanup_server_dir(&target);
+        return Err(format!("Failed to copy server: {}", e));
+    }
+
+    let clone = ServerConfig {
+        id: Uuid::new_v4().to_string(),
+        name,
+        path: target.to_string_lossy().to_string(),
+        created_at: Utc::now().timestamp(),
+        ..source
+    };
+
+    if let Err(e) = save_server_config(&clone) {
+        cleanup_server_dir(&target);
+        return Err(e);
+    }
+
+    Ok(clone)
+}
+
+/// Templates
+
+fn template_dir(template_id: &str) -> Result<PathBuf, String> {
+    // Ids are generated here, anything else is someone poking at the path
+    if Uuid::parse_str(template_id).is_err() {
+        return Err("Invalid template id".into());
+    }
+
+    Ok(templates_dir().join(template_id))
+}
+
+pub fn load_template(template_id: &str) -> Result<ServerTemplate, String> {
+    let raw = fs::read_to_string(template_dir(template_id)?.join(TEMPLATE_FILE))
+        .map_err(|_| "Template not found".to_string())?;
+
+    serde_json::from_str(&raw).map_err(|e| e.to_string())
+}
+
+/// Copies a template's files into a new server directory
+pub fn instantiate_template(
+    template: &ServerTemplate,
+    server_path: &PathBuf,
+    job: &CreationJob,
+) -> Result<InstalledServer, String> {
+    job.check_cancelled()?;
+    job.set_phase(CreatePhase::WritingFiles);
+
+    copy_dir_excluding(
+        &template_dir(&template.id)?,
+        server_path,
+        &[TEMPLATE_FILE.to_string()],
+    )?;
+
+    if template.loader != LoaderType::Bedrock && !server_path.join("eula.txt").exists() {
+        fs::write(server_path.join("eula.txt"), "eula=true\n").map_err(|e| e.to_string())?;
+    }
+
+    Ok(InstalledServer {
+        java_major: template.java_major,
+        loader_build: template.loader_build.clone(),
+        installer_version: template.installer_version.clone(),
+    })
+}
+
+#[tauri::command]
+pub fn save_server_template(
+    server_id: String,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ServerTemplate, String> {
+    ensure_stopped(
+        &server_id,
+        &state,
+        "Stop the server before saving it as a template",
+    )?;
+
+    let server = find_server(&server_id)?;
+    let name = validate_server_name(&name)?;
+
+    let template = ServerTemplate {
+        id: Uuid::new_v4().to_string(),
+        name,
+        version: server.version.clone(),
+        loader: server.loader.clone(),
+        ram_gb: server.ram_gb,
+        created_at: Utc::now().timestamp(),
+        java_major: server.java_major,
+        loader_build: server.loader_build.clone(),
+        installer_version: server.installer_version.clone(),
+        server_jar: server.server_jar.clone(),
+        crossplay: server.crossplay.clone(),
+    };
+
+    let dir = template_dir(&template.id)?;
+    let exclude = excluded_entries(&server, false);
+
+    let saved = copy_dir_excluding(&PathBuf::from(&server.path), &dir, &exclude).and_then(|_| {
+        fs::write(
+            dir.join(TEMPLATE_FILE),
+            serde_json::to_string_pretty(&template).unwrap(),
+        )
+        .map_err(|e| e.to_string())
+    });
+
+    if let Err(e) = saved {
+        fs::remove_dir_all(&dir).ok();
+        return Err(format!("Failed to save template: {}", e));
+    }
+
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn list_server_templates() -> Result<Vec<ServerTemplate>, String> {
+    let Ok(entries) = fs::read_dir(templates_dir()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut templates: Vec<ServerTemplate> = entries
+        .flatten()
+        .filter_map(|e| fs::read_to_string(e.path().join(TEMPLATE_FILE)).ok())
+        .filter_map(|raw| serde_json::from_str(&raw).ok())
+        .collect();
+
+    templates.sort_by_key(|t| t.name.to_lowercase());
+
+    Ok(templates)
+}
+
+#[tauri::command]
+pub fn delete_server_template(template_id: String) -> Result<(), String> {
+    let dir = template_dir(&template_id)?;
+
+    if !dir.join(TEMPLATE_FILE).exists() {
+        return Err("Template not found".into());
+    }
+
+    fs::remove_dir_all(dir).map_err(|e| e.to_string())
+}
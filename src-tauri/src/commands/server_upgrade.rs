@@ -0,0 +1,322 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::commands::creation_jobs::{CreatePhase, CreationJob, CANCELLED};
+use crate::commands::crossplay_manager::crossplay_mode;
+use crate::commands::java_manager::{ensure_java, server_java};
+use crate::commands::server_creation::{install_server_files, InstallTarget, LoaderType};
+use crate::commands::server_management::{
+    ensure_stopped, find_server, java_server_command, parse_status_line, save_server_config,
+    ServerConfig, ServerStatus,
+};
+use crate::commands::versions_loaders::fetch_version_manifest;
+use crate::state::app_state::AppState;
+use crate::utils::download::DownloadObserver;
+use crate::utils::network::http_client;
+use crate::utils::path::{backups_dir, copy_dir_all};
+
+/// UPGRADING SERVERS
+///
+/// Moves an existing server to another Minecraft version or loader in place,
+/// the directory doesn't depend on either.
+/// The server directory is backed up first, the old server jar and loader
+/// files are replaced, and any failure restores the backup. Worlds, configs,
+/// mods and plugins are kept as they are.
+
+// Written by loader installers and replaced on upgrade
+const INSTALL_FILES: &[&str] = &[
+    "server.jar",
+    "fabric-server-launch.jar",
+    "fabric-server-launcher.properties",
+    "run.sh",
+    "run.bat",
+    "bedrock_server",
+    "bedrock_server_how_to.html",
+    "release-notes.txt",
+];
+
+const INSTALL_DIRS: &[&str] = &["libraries", ".fabric", "versions", "cache"];
+
+// Kept from the old server when the new install ships its own defaults
+const PRESERVED_FILES: &[&str] = &[
+    "server.properties",
+    "eula.txt",
+    "allowlist.json",
+    "permissions.json",
+];
+
+#[derive(Debug, Serialize)]
+pub struct UpgradeServerResult {
+    pub server: ServerConfig,
+    pub backup_path: String,
+    pub job_id: String,
+}
+
+fn remove_install_files(server_path: &Path) {
+    for file in INSTALL_FILES {
+        fs::remove_file(server_path.join(file)).ok();
+    }
+
+    for dir in INSTALL_DIRS {
+        fs::remove_dir_all(server_path.join(dir)).ok();
+    }
+
+    // Versioned loader jars, e.g. forge-1.20.1-47.2.0-shim.jar or minecraft_server.1.12.2.jar
+    if let Ok(entries) = fs::read_dir(server_path) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if name.ends_with(".jar")
+                && (name.starts_with("forge-") || name.starts_with("minecraft_server."))
+            {
+                fs::remove_file(entry.path()).ok();
+            }
+        }
+    }
+}
+
+fn restore_preserved_files(backup: &Path, server_path: &Path) {
+    for file in PRESERVED_FILES {
+        let saved = backup.join(file);
+
+        if saved.exists() {
+            fs::copy(&saved, server_path.join(file)).ok();
+        }
+    }
+}
+
+/// Empties the server directory and copies the backup back into it.
+/// `server_path` must be canonical: a linked import would otherwise lose
+/// its symlink to a copy and leave the linked directory half-upgraded.
+fn restore_backup(backup: &Path, server_path: &Path) -> Result<(), String> {
+    for entry in fs::read_dir(server_path).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+
+        match entry.file_type().is_ok_and(|t| t.is_dir()) {
+            true => fs::remove_dir_all(&path).ok(),
+            false => fs::remove_file(&path).ok(),
+        };
+    }
+
+    copy_dir_all(backup, server_path)
+}
+
+/// Worlds can't be opened by older versions, so refuse to go back.
+/// Unknown versions (e.g. removed snapshots) are let through.
+async fn check_not_downgrade(from: &str, to: &str) -> Result<(), String> {
+    let manifest = fetch_version_manifest(&http_client()).await?.data;
+    let versions = manifest["versions"].as_array().ok_or("Invalid manifest")?;
+
+    // The manifest lists the newest version first
+    let position = |id: &str| versions.iter().position(|v| v["id"] == id);
+
+    if let (Some(from_pos), Some(to_pos)) = (position(from), position(to)) {
+        if to_pos > from_pos {
+            return Err(format!(
+                "Downgrading from {} to {} is not supported, worlds can't be opened by older versions",
+                from, to
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Launches the server once with --forceUpgrade so every chunk is converted
+/// up front, then stops it as soon as it's up
+async fn run_force_upgrade(
+    server: &ServerConfig,
+    java: &PathBuf,
+    job: &CreationJob,
+) -> Result<(), String> {
+    let mut child = java_server_command(server, java)?
+        .arg("--forceUpgrade")
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start world upgrade: {}", e))?;
+
+    let mut stdin = child.stdin.take();
+    let stdout = child.stdout.take().ok_or("Failed to read server output")?;
+
+    let reader = {
+        let job = job.clone();
+        let loader = server.loader.clone();
+
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().flatten() {
+                if parse_status_line(&loader, &line) == Some(ServerStatus::Running) {
+                    if let Some(mut stdin) = stdin.take() {
+                        writeln!(stdin, "stop").ok();
+                    }
+                }
+
+                job.log(line);
+            }
+        })
+    };
+
+    let success = job.wait_installer(child).await?;
+    reader.join().ok();
+
+    if !success {
+        return Err("Server exited with an error while upgrading the world".into());
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn upgrade_server(
+    server_id: String,
+    version: String,
+    loader: Option<LoaderType>,
+    loader_build: Option<String>,
+    force_upgrade: Option<bool>,
+    job_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<UpgradeServerResult, String> {
+    ensure_stopped(&server_id, &state, "Stop the server before upgrading it")?;
+
+    let app = {
+        let guard = state.app_handle.lock().unwrap();
+        guard
+            .clone()
+            .ok_or("App handle not initialized")?
+    };
+
+    let java_base = {
+        let guard = state.java_base_dir.lock().unwrap();
+        guard
+            .clone()
+            .ok_or("Java base directory not initialized")?
+    };
+
+    let mut server = find_server(&server_id)?;
+
+    let loader = loader.unwrap_or(server.loader.clone());
+    let is_bedrock = |l: &LoaderType| matches!(l, LoaderType::Bedrock);
+
+    if is_bedrock(&loader) != is_bedrock(&server.loader) {
+        return Err("Java and Bedrock worlds can't be converted into each other".into());
+    }
+
+    if let Some(crossplay) = server.crossplay.as_ref().filter(|c| c.enabled) {
+        if crossplay_mode(&loader).ok() != Some(crossplay.mode) {
+            return Err("Disable crossplay before changing the loader".into());
+        }
+    }
+
+    if !is_bedrock(&loader) {
+        check_not_downgrade(&server.version, &version).await?;
+    }
+
+    // Linked imports point elsewhere, back up and restore the real directory
+    let server_path = fs::canonicalize(&server.path)
+        .map_err(|e| format!("Failed to resolve server directory: {}", e))?;
+    let old_version = server.version.clone();
+
+    let job = CreationJob::new(job_id, app);
+    state
+        .creation_jobs
+        .lock()
+        .unwrap()
+        .insert(job.id.clone(), job.clone());
+
+    job.set_phase(CreatePhase::BackingUp);
+
+    let backup = backups_dir()
+        .join(&server.id)
+        .join(format!("{}-{}", Utc::now().format("%Y%m%d-%H%M%S"), old_version));
+
+    if let Err(e) = copy_dir_all(&server_path, &backup) {
+        state.creation_jobs.lock().unwrap().remove(&job.id);
+        fs::remove_dir_all(&backup).ok();
+        job.set_phase(CreatePhase::Failed);
+        return Err(format!("Failed to back up server: {}", e));
+    }
+
+    let result = async {
+        remove_install_files(&server_path);
+
+        // The custom jar the server launched from goes too. Removing it before
+        // the install keeps a same-named jar from the new loader, and a failed
+        // upgrade brings it back from the backup.
+        if let Some(jar) = server
+            .server_jar
+            .as_deref()
+            .and_then(|j| Path::new(j).file_name())
+        {
+            fs::remove_file(server_path.join(jar)).ok();
+        }
+
+        let target = InstallTarget {
+            name: &server.name,
+            version: &version,
+            loader: &loader,
+            loader_build: loader_build.as_deref(),
+            installer_version: None,
+        };
+        let installed = install_server_files(&target, &server_path, &java_base, &job).await?;
+
+        job.check_cancelled()?;
+        job.set_phase(CreatePhase::WritingFiles);
+        restore_preserved_files(&backup, &server_path);
+
+        server.version = version.clone();
+        server.loader = loader.clone();
+        server.java_major = installed.java_major;
+        server.loader_build = installed.loader_build;
+        server.installer_version = installed.installer_version;
+        server.server_jar = None; // the new install uses the loader's usual jar
+
+        if force_upgrade.unwrap_or(false) && !is_bedrock(&loader) {
+            job.set_phase(CreatePhase::InstallingJava);
+            let java = ensure_java(
+                &java_base,
+                server_java(server.java_major, &server.version),
+                &job,
+            )
+            .await?;
+
+            job.set_phase(CreatePhase::UpgradingWorld);
+            run_force_upgrade(&server, &java, &job).await?;
+        }
+
+        // Inside the job so a failed write restores the backup as well
+        save_server_config(&server)?;
+
+        Ok::<(), String>(())
+    }
+    .await;
+
+    state.creation_jobs.lock().unwrap().remove(&job.id);
+
+    if let Err(err) = result {
+        if let Err(e) = restore_backup(&backup, &server_path) {
+            eprintln!("Failed to restore backup {:?}: {}", backup, e);
+        }
+
+        if job.is_cancelled() {
+            job.set_phase(CreatePhase::Cancelled);
+            return Err(CANCELLED.into());
+        }
+
+        job.set_phase(CreatePhase::Failed);
+        return Err(err);
+    }
+
+    job.set_phase(CreatePhase::Done);
+
+    Ok(UpgradeServerResult {
+        server,
+        backup_path: backup.to_string_lossy().to_string(),
+        job_id: job.id.clone(),
+    })
+}
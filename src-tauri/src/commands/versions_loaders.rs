@@ -1,41 +1,257 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-// #[derive(Deserialize, Serialize)]
-// pub struct McVersion {
-//     pub id: String,
-//     pub r#type: String, // type is a reserved keyword in Rust, thats why r#
-// }
+use crate::commands::manifest_cache::{
+    cached_json, fetch_cached, fetch_json_cached, Fetched, Freshness,
+};
+use crate::state::app_state::AppState;
+use crate::utils::network::{endpoint, http_client, Endpoint};
+
+/// Minecraft versions from the Mojang manifest
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McVersionType {
+    Release,
+    Snapshot,
+    PreRelease,
+    OldBeta,
+    OldAlpha,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McVersion {
+    pub id: String,
+    pub version_type: McVersionType,
+    pub release_time: String,
+    // From the version JSON, None until `get_mc_version_details` fetched it
+    pub java_major: Option<u32>, // javaVersion.majorVersion
+    pub has_server: Option<bool>, // false for versions Mojang ships no server.jar for
+}
+
+/// Which non-release versions to include, releases are always listed
+#[derive(Debug, Default, Deserialize)]
+pub struct McVersionFilter {
+    #[serde(default)]
+    pub snapshots: bool,
+    #[serde(default)]
+    pub pre_releases: bool,
+    #[serde(default)]
+    pub old: bool,
+}
+
+impl McVersionFilter {
+    fn allows(&self, version_type: McVersionType) -> bool {
+        match version_type {
+            McVersionType::Release => true,
+            McVersionType::Snapshot => self.snapshots,
+            McVersionType::PreRelease => self.pre_releases,
+            McVersionType::OldBeta | McVersionType::OldAlpha => self.old,
+        }
+    }
+}
+
+// Pre-releases and release candidates are listed as "snapshot" in the manifest
+fn classify_version(id: &str, manifest_type: &str) -> McVersionType {
+    match manifest_type {
+        "release" => McVersionType::Release,
+        "old_beta" => McVersionType::OldBeta,
+        "old_alpha" => McVersionType::OldAlpha,
+        _ if id.contains("-pre") || id.contains("-rc") || id.contains(" Pre-Release ") => {
+            McVersionType::PreRelease
+        }
+        _ => McVersionType::Snapshot,
+    }
+}
+
+/// Falls back to the last manifest fetched when Mojang can't be reached
+pub async fn fetch_version_manifest(client: &reqwest::Client) -> Result<Fetched<Value>, String> {
+    fetch_json_cached(
+        client,
+        &format!("{}/mc/game/version_manifest.json", endpoint(Endpoint::MojangMeta)),
+    )
+    .await
+    .map_err(|e| format!("Failed to fetch Minecraft version manifest: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_mc_versions(
+    filter: Option<McVersionFilter>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<McVersion>, String> {
+    let filter = filter.unwrap_or_default();
+    let client = http_client();
+
+    let manifest = fetch_version_manifest(&client).await?;
+    state.manifest_status.lock().unwrap().versions = Some(Freshness::from(&manifest));
+
+    let resp = manifest.data;
+    let versions = resp["versions"].as_array().ok_or("Invalid manifest")?;
+
+    // Details live in a JSON per version, only the ones fetched before are filled in here
+    let cache = state.mc_version_cache.lock().unwrap();
+
+    Ok(versions
+        .iter()
+        .filter_map(|v| {
+            let id = v["id"].as_str()?;
+            let version_type = classify_version(id, v["type"].as_str()?);
+
+            if !filter.allows(version_type) {
+                return None;
+            }
+
+            let details = cache.get(id);
+
+            Some(McVersion {
+                id: id.to_string(),
+                version_type,
+                release_time: v["releaseTime"].as_str().unwrap_or_default().to_string(),
+                java_major: details.and_then(|d| d.java_major),
+                has_server: details.map(|d| d.has_server),
+            })
+        })
+        .collect())
+}
+
+/// Java version and server availability of one version, for the version being picked
+#[tauri::command]
+pub async fn get_mc_version_details(
+    version: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<McVersionDetails, String> {
+    if let Some(details) = state.mc_version_cache.lock().unwrap().get(&version) {
+        return Ok(details.clone());
+    }
+
+    let client = http_client();
+    let manifest = fetch_version_manifest(&client).await?;
+
+    let url = manifest.data["versions"]
+        .as_array()
+        .ok_or("Invalid manifest")?
+        .iter()
+        .find(|v| v["id"].as_str() == Some(version.as_str()))
+        .and_then(|v| v["url"].as_str())
+        .ok_or(format!("Minecraft {} not found", version))?
+        .to_string();
+
+    let details = fetch_version_details(&client, &url, manifest.offline).await?;
+
+    state
+        .mc_version_cache
+        .lock()
+        .unwrap()
+        .insert(version, details.clone());
 
+    Ok(details)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct McVersionDetails {
+    pub java_major: Option<u32>,
+    pub has_server: bool,
+}
+
+/// Offline, only details fetched on an earlier run are available
+pub async fn fetch_version_details(
+    client: &reqwest::Client,
+    url: &str,
+    offline: bool,
+) -> Result<McVersionDetails, String> {
+    let details = if offline {
+        cached_json(url).ok_or("Version details not cached")?.data
+    } else {
+        fetch_json_cached(client, url).await?.data
+    };
+
+    Ok(McVersionDetails {
+        java_major: details["javaVersion"]["majorVersion"]
+            .as_u64()
+            .map(|v| v as u32),
+        has_server: details["downloads"]["server"]["url"].is_string(),
+    })
+}
+
+/// Bedrock Dedicated Server versions
+///
+/// Mojang only publishes a link to the newest Bedrock server build, so the
+/// version list is derived from that link's file name.
 #[tauri::command]
-pub async fn get_mc_versions() -> Result<Vec<String>, String> {
-    let resp: Value = reqwest::get("https://launchermeta.mojang.com/mc/game/version_manifest.json")
+pub async fn get_bedrock_versions() -> Result<Vec<String>, String> {
+    let url = fetch_bedrock_download_link().await?;
+    let version = bedrock_version_from_url(&url).ok_or("Invalid Bedrock download link")?;
+
+    Ok(vec![version])
+}
+
+pub async fn fetch_bedrock_download_link() -> Result<String, String> {
+    let resp: Value = http_client()
+        .get(format!("{}/api/v1.0/download/links", endpoint(Endpoint::BedrockLinks)))
+        .header("User-Agent", "Cubely")
+        .send()
         .await
         .map_err(|e| e.to_string())?
         .json()
         .await
         .map_err(|e| e.to_string())?;
 
-    let versions = resp["versions"].as_array().ok_or("Invalid manifest")?;
-
-    let releases: Vec<String> = versions
+    resp["result"]["links"]
+        .as_array()
+        .ok_or("Invalid Bedrock download links")?
         .iter()
-        .filter(|v| v["type"] == "release")
-        .filter_map(|v| v["id"].as_str().map(String::from))
-        .collect();
+        .find(|l| l["downloadType"] == "serverBedrockLinux")
+        .and_then(|l| l["downloadUrl"].as_str())
+        .map(String::from)
+        .ok_or("No Bedrock server download for Linux found".into())
+}
 
-    Ok(releases)
+// ".../bin-linux/bedrock-server-1.21.51.02.zip" -> "1.21.51.02"
+pub fn bedrock_version_from_url(url: &str) -> Option<String> {
+    url.rsplit('/')
+        .next()?
+        .strip_prefix("bedrock-server-")?
+        .strip_suffix(".zip")
+        .map(String::from)
 }
 
 use std::collections::HashSet;
 use std::sync::Mutex;
 use tokio::time::{sleep, Duration};
 
-use crate::state::app_state::AppState;
-
 pub struct LoaderSupportCache {
     pub fabric_versions: HashSet<String>,
     pub forge_versions: HashSet<String>,
+    pub neoforge_versions: HashSet<String>,
+    pub paper_versions: HashSet<String>,
+}
+
+/// Fetches every loader list, falling back to the stored copies.
+/// Freshness is that of the oldest list, offline if any came from disk or is missing.
+pub async fn load_loader_support() -> (LoaderSupportCache, Freshness) {
+    let fabric = fetch_fabric_versions().await;
+    let forge = fetch_forge_versions().await;
+    let neoforge = fetch_neoforge_versions().await;
+    let paper = fetch_paper_versions().await;
+
+    let lists = [&fabric, &forge, &neoforge, &paper];
+    let freshness = Freshness {
+        fetched_at: lists
+            .iter()
+            .filter_map(|l| l.as_ref().map(|l| l.fetched_at))
+            .min()
+            .unwrap_or(0),
+        offline: lists.iter().any(|l| l.as_ref().map_or(true, |l| l.offline)),
+    };
+
+    let support = LoaderSupportCache {
+        fabric_versions: fabric.map(|l| l.data).unwrap_or_default(),
+        forge_versions: forge.map(|l| l.data).unwrap_or_default(),
+        neoforge_versions: neoforge.map(|l| l.data).unwrap_or_default(),
+        paper_versions: paper.map(|l| l.data).unwrap_or_default(),
+    };
+
+    (support, freshness)
 }
 
 #[derive(Deserialize, Serialize)]
@@ -43,6 +259,8 @@ pub struct SupportLoaders {
     vanilla: bool,
     fabric: bool,
     forge: bool,
+    neoforge: bool,
+    paper: bool,
 }
 
 #[tauri::command]
@@ -56,6 +274,8 @@ pub async fn get_supported_loaders<'a>(
                 vanilla: true,
                 fabric: cache.fabric_versions.contains(&version),
                 forge: cache.forge_versions.contains(&version),
+                neoforge: cache.neoforge_versions.contains(&version),
+                paper: cache.paper_versions.contains(&version),
             });
         }
 
@@ -63,33 +283,38 @@ pub async fn get_supported_loaders<'a>(
     }
 }
 
-pub async fn fetch_fabric_versions() -> HashSet<String> {
-    let Ok(resp) = reqwest::get("https://meta.fabricmc.net/v2/versions/game").await else {
-        return HashSet::new();
-    };
-
-    let Ok(list) = resp.json::<Vec<serde_json::Value>>().await else {
-        return HashSet::new();
-    };
+pub async fn fetch_fabric_versions() -> Option<Fetched<HashSet<String>>> {
+    let list = fetch_json_cached(
+        &http_client(),
+        &format!("{}/v2/versions/game", endpoint(Endpoint::FabricMeta)),
+    )
+    .await
+    .ok()?;
 
-    list.iter()
-        .filter_map(|v| v["version"].as_str().map(String::from))
-        .collect()
+    Some(list.map(|list| {
+        list.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v["version"].as_str().map(String::from))
+            .collect()
+    }))
 }
 
-pub async fn fetch_forge_versions() -> HashSet<String> {
-    let Ok(resp) = reqwest::get(
-        "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml",
+pub async fn fetch_forge_versions() -> Option<Fetched<HashSet<String>>> {
+    let metadata = fetch_cached(
+        &http_client(),
+        &format!(
+            "{}/net/minecraftforge/forge/maven-metadata.xml",
+            endpoint(Endpoint::ForgeMaven)
+        ),
     )
     .await
-    else {
-        return HashSet::new();
-    };
+    .ok()?;
 
-    let Ok(text) = resp.text().await else {
-        return HashSet::new();
-    };
+    Some(metadata.map(|text| forge_mc_versions(&text)))
+}
 
+fn forge_mc_versions(text: &str) -> HashSet<String> {
     text.lines()
         .filter_map(|line| {
             let line = line.trim();
@@ -109,6 +334,316 @@ pub async fn fetch_forge_versions() -> HashSet<String> {
         .collect()
 }
 
+pub async fn fetch_neoforge_versions() -> Option<Fetched<HashSet<String>>> {
+    let metadata = fetch_neoforge_metadata().await.ok()?;
+
+    Some(metadata.map(|text| {
+        maven_versions(&text)
+            .iter()
+            .filter_map(|build| neoforge_mc_version(build))
+            .collect()
+    }))
+}
+
+pub async fn fetch_paper_versions() -> Option<Fetched<HashSet<String>>> {
+    let project = fetch_json_cached(
+        &http_client(),
+        &format!("{}/v3/projects/paper", endpoint(Endpoint::PaperApi)),
+    )
+    .await
+    .ok()?;
+
+    // { "versions": { "1.21": ["1.21.1", "1.21"], ... } }
+    Some(project.map(|project| {
+        project["versions"]
+            .as_object()
+            .map(|groups| {
+                groups
+                    .values()
+                    .filter_map(|v| v.as_array())
+                    .flatten()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }))
+}
+
+/// Fabric loader versions
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FabricLoader {
+    pub version: String,
+    pub stable: bool,
+}
+
+/// Loader versions that support `mc_version`, newest first
+pub async fn fetch_fabric_loaders(mc_version: &str) -> Result<Vec<FabricLoader>, String> {
+    // [{ "loader": { "version": "0.16.10", "stable": true }, "intermediary": {...} }, ...]
+    let list = fetch_json_cached(
+        &http_client(),
+        &format!(
+            "{}/v2/versions/loader/{}",
+            endpoint(Endpoint::FabricMeta),
+            mc_version
+        ),
+    )
+    .await
+    .map_err(|e| format!("Failed to fetch Fabric loader versions: {}", e))?
+    .data;
+
+    Ok(list
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| {
+            Some(FabricLoader {
+                version: v["loader"]["version"].as_str()?.to_string(),
+                stable: v["loader"]["stable"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_fabric_loaders(
+    mc_version: String,
+    include_unstable: Option<bool>,
+) -> Result<Vec<FabricLoader>, String> {
+    let mut loaders = fetch_fabric_loaders(&mc_version).await?;
+
+    if !include_unstable.unwrap_or(false) {
+        loaders.retain(|l| l.stable);
+    }
+
+    if loaders.is_empty() {
+        return Err(format!("No Fabric loader found for Minecraft {}", mc_version));
+    }
+
+    Ok(loaders)
+}
+
+/// Fabric installer versions, newest first
+pub async fn fetch_fabric_installers() -> Result<Vec<FabricLoader>, String> {
+    // [{ "url": "...", "maven": "...", "version": "1.0.1", "stable": true }, ...]
+    let list = fetch_json_cached(
+        &http_client(),
+        &format!("{}/v2/versions/installer", endpoint(Endpoint::FabricMeta)),
+    )
+    .await
+    .map_err(|e| format!("Failed to fetch Fabric installer versions: {}", e))?
+    .data;
+
+    Ok(list
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| {
+            Some(FabricLoader {
+                version: v["version"].as_str()?.to_string(),
+                stable: v["stable"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_fabric_installers(
+    include_unstable: Option<bool>,
+) -> Result<Vec<FabricLoader>, String> {
+    let mut installers = fetch_fabric_installers().await?;
+
+    if !include_unstable.unwrap_or(false) {
+        installers.retain(|i| i.stable);
+    }
+
+    if installers.is_empty() {
+        return Err("No Fabric installer versions found".into());
+    }
+
+    Ok(installers)
+}
+
+/// Forge builds
+///
+/// Every build comes from maven-metadata.xml (newest first), the recommended
+/// and latest markers from Forge's promotions.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForgeBuild {
+    pub version: String, // Maven version, "1.20.1-47.2.0"
+    pub build: String,   // Forge part, "47.2.0"
+    pub recommended: bool,
+    pub latest: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForgeBuilds {
+    pub recommended: Option<String>,
+    pub latest: Option<String>,
+    pub builds: Vec<ForgeBuild>,
+}
+
+impl ForgeBuilds {
+    /// The build a server gets when none is picked
+    pub fn default_build(&self) -> Option<&ForgeBuild> {
+        self.builds
+            .iter()
+            .find(|b| b.recommended)
+            .or_else(|| self.builds.iter().find(|b| b.latest))
+            .or_else(|| self.builds.first())
+    }
+
+    /// Finds a build by its Forge part or full Maven version
+    pub fn find(&self, build: &str) -> Option<&ForgeBuild> {
+        self.builds
+            .iter()
+            .find(|b| b.build == build || b.version == build)
+    }
+}
+
+pub async fn fetch_forge_builds(mc_version: &str) -> Result<ForgeBuilds, String> {
+    let client = http_client();
+
+    let metadata = fetch_cached(
+        &client,
+        &format!(
+            "{}/net/minecraftforge/forge/maven-metadata.xml",
+            endpoint(Endpoint::ForgeMaven)
+        ),
+    )
+    .await
+    .map_err(|e| format!("Failed to fetch Forge builds: {}", e))?
+    .data;
+
+    // { "promos": { "1.20.1-recommended": "47.2.0", "1.20.1-latest": "47.3.0", ... } }
+    let promotions = fetch_json_cached(
+        &client,
+        &format!(
+            "{}/net/minecraftforge/forge/promotions_slim.json",
+            endpoint(Endpoint::ForgeFiles)
+        ),
+    )
+    .await
+    .map(|p| p.data)
+    .unwrap_or_default();
+
+    let promo = |kind: &str| {
+        promotions["promos"][format!("{}-{}", mc_version, kind)]
+            .as_str()
+            .map(String::from)
+    };
+
+    let recommended = promo("recommended");
+    let latest = promo("latest");
+    let prefix = format!("{}-", mc_version);
+    // Old builds repeat the game version, "1.7.10-10.13.4.1614-1.7.10"
+    let suffix = format!("-{}", mc_version);
+
+    let builds = metadata
+        .lines()
+        .filter_map(|line| {
+            let version = line
+                .trim()
+                .strip_prefix("<version>")?
+                .strip_suffix("</version>")?;
+            let build = version.strip_prefix(&prefix)?;
+            let build = build.strip_suffix(&suffix).unwrap_or(build).to_string();
+
+            Some(ForgeBuild {
+                version: version.to_string(),
+                recommended: recommended.as_deref() == Some(build.as_str()),
+                latest: latest.as_deref() == Some(build.as_str()),
+                build,
+            })
+        })
+        .collect();
+
+    Ok(ForgeBuilds {
+        recommended,
+        latest,
+        builds,
+    })
+}
+
+#[tauri::command]
+pub async fn get_forge_builds(mc_version: String) -> Result<ForgeBuilds, String> {
+    let builds = fetch_forge_builds(&mc_version).await?;
+
+    if builds.builds.is_empty() {
+        return Err(format!("No Forge builds found for Minecraft {}", mc_version));
+    }
+
+    Ok(builds)
+}
+
+/// NeoForge builds
+///
+/// NeoForge versions encode the Minecraft version they're for: "21.1.77" is a
+/// build for 1.21.1, "21.0.167" one for 1.21. Builds for 1.20.1 live under a
+/// different artifact and aren't listed.
+
+async fn fetch_neoforge_metadata() -> Result<Fetched<String>, String> {
+    fetch_cached(
+        &http_client(),
+        &format!(
+            "{}/net/neoforged/neoforge/maven-metadata.xml",
+            endpoint(Endpoint::NeoForgeMaven)
+        ),
+    )
+    .await
+}
+
+/// Every `<version>` of a maven-metadata.xml, in file order
+fn maven_versions(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("<version>")?
+                .strip_suffix("</version>")
+                .map(String::from)
+        })
+        .collect()
+}
+
+pub fn neoforge_mc_version(build: &str) -> Option<String> {
+    let mut parts = build.split('.');
+    let major: u32 = parts.next()?.parse().ok()?;
+    let minor: u32 = parts.next()?.parse().ok()?;
+    parts.next()?;
+
+    Some(match minor {
+        0 => format!("1.{}", major),
+        _ => format!("1.{}.{}", major, minor),
+    })
+}
+
+/// Builds for `mc_version`, newest first
+pub async fn fetch_neoforge_builds(mc_version: &str) -> Result<Vec<String>, String> {
+    let metadata = fetch_neoforge_metadata()
+        .await
+        .map_err(|e| format!("Failed to fetch NeoForge builds: {}", e))?
+        .data;
+
+    // Unlike Forge's, NeoForge's metadata lists the oldest build first
+    Ok(maven_versions(&metadata)
+        .into_iter()
+        .rev()
+        .filter(|build| neoforge_mc_version(build).as_deref() == Some(mc_version))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_neoforge_builds(mc_version: String) -> Result<Vec<String>, String> {
+    let builds = fetch_neoforge_builds(&mc_version).await?;
+
+    if builds.is_empty() {
+        return Err(format!("No NeoForge builds found for Minecraft {}", mc_version));
+    }
+
+    Ok(builds)
+}
+
 // To Make sure forge is only for versions above or equal to 1.7.10 as automation fails for below versions
 fn is_supported_forge_mc(version: &str) -> bool {
     if version == "1.7.10" {
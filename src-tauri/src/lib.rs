@@ -5,21 +5,46 @@ pub mod state;
 pub mod utils;
 
 use crate::commands::server_creation::create_server;
+use crate::commands::creation_jobs::cancel_server_creation;
+use crate::commands::server_upgrade::upgrade_server;
+use crate::commands::server_import::{import_server, inspect_server};
+use crate::commands::server_templates::{clone_server, save_server_template, list_server_templates, delete_server_template};
+use crate::commands::modpack_import::{inspect_mrpack, import_mrpack};
+use crate::commands::curseforge_import::{inspect_curseforge_pack, import_curseforge_pack};
+use crate::commands::modpack_export::export_server_modpack;
+use crate::commands::mod_inventory::list_mods;
+use crate::commands::mod_checker::check_server_mods;
+use crate::commands::modrinth_browser::{search_modrinth, get_modrinth_project, get_modrinth_versions, install_modrinth_mod, remove_mod};
+use crate::commands::mod_updates::{check_mod_updates, apply_mod_updates, get_pending_mod_update, rollback_mod_updates};
+use crate::commands::mod_profiles::{list_mod_files, set_mod_enabled, list_mod_profiles, save_mod_profile, delete_mod_profile, apply_mod_profile};
+use crate::commands::mod_bisect::{start_mod_bisect, get_mod_bisect, record_bisect_result, cancel_mod_bisect};
+use crate::commands::plugin_manager::{list_plugins, search_hangar, get_hangar_versions, install_hangar_plugin};
 use crate::commands::server_management::get_active_server;
 use crate::commands::server_management::list_servers;
 use crate::commands::server_management::read_server_config;
 use crate::commands::server_management::update_server_config;
+use crate::commands::server_management::rename_server;
+use crate::commands::server_management::migrate_server_layout;
 use crate::commands::server_management::read_server_properties;
 use crate::commands::server_management::start_server;
 use crate::commands::server_management::stop_server;
 use crate::commands::server_management::update_server_properties;
+use crate::commands::server_management::read_bedrock_server_properties;
+use crate::commands::server_management::update_bedrock_server_properties;
 use crate::commands::server_management::delete_server;
 use crate::commands::server_management::send_mc_command;
-use crate::commands::versions_loaders::fetch_fabric_versions;
-use crate::commands::versions_loaders::fetch_forge_versions;
-use crate::commands::versions_loaders::get_mc_versions;
+use crate::commands::versions_loaders::load_loader_support;
+use crate::commands::versions_loaders::{get_mc_versions, get_mc_version_details};
+use crate::commands::versions_loaders::get_bedrock_versions;
 use crate::commands::versions_loaders::get_supported_loaders;
+use crate::commands::versions_loaders::get_forge_builds;
+use crate::commands::versions_loaders::get_neoforge_builds;
+use crate::commands::versions_loaders::{get_fabric_loaders, get_fabric_installers};
 use crate::commands::misc::open_folder;
+use crate::commands::artifact_cache::{get_cache_info, prune_cache};
+use crate::commands::manifest_cache::get_manifest_status;
+use crate::commands::network_settings::{get_network_settings, update_network_settings};
+use crate::commands::crossplay_manager::{enable_crossplay, disable_crossplay};
 use crate::commands::discord_rpc::{init_discord_rpc, set_idle, discord_set_server_running};
 use crate::commands::versions_loaders::LoaderSupportCache;
 use crate::state::app_state::AppState;
@@ -56,18 +81,24 @@ pub fn run() {
                 )?;
             }
 
-            // Versions cache
+            // Move servers out of the old servers/<version>/<name> layout
+            migrate_server_layout();
+
+            // Versions cache, served from disk while offline and refreshed in the background
             let cache = app.state::<AppState>().loader_cache.clone();
+            let status = app.state::<AppState>().manifest_status.clone();
 
             tauri::async_runtime::spawn(async move {
-                let fabric_versions = fetch_fabric_versions().await;
-                let forge_versions = fetch_forge_versions().await;
-
-                let mut cache = cache.lock().unwrap();
-                *cache = Some(LoaderSupportCache {
-                    fabric_versions,
-                    forge_versions,
-                });
+                loop {
+                    let (support, freshness) = load_loader_support().await;
+
+                    *cache.lock().unwrap() = Some(support);
+                    status.lock().unwrap().loaders = Some(freshness);
+
+                    // Retry soon while offline, otherwise refresh a few times a day
+                    let wait = if freshness.offline { 60 } else { 6 * 60 * 60 };
+                    tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+                }
             });
 
             // Store app handle
@@ -138,20 +169,74 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             greet,
             create_server,
+            cancel_server_creation,
+            upgrade_server,
+            inspect_server,
+            import_server,
+            clone_server,
+            save_server_template,
+            list_server_templates,
+            delete_server_template,
+            inspect_mrpack,
+            import_mrpack,
+            inspect_curseforge_pack,
+            import_curseforge_pack,
+            export_server_modpack,
+            list_mods,
+            check_server_mods,
+            search_modrinth,
+            get_modrinth_project,
+            get_modrinth_versions,
+            install_modrinth_mod,
+            remove_mod,
+            check_mod_updates,
+            apply_mod_updates,
+            get_pending_mod_update,
+            rollback_mod_updates,
+            list_mod_files,
+            set_mod_enabled,
+            list_mod_profiles,
+            save_mod_profile,
+            delete_mod_profile,
+            apply_mod_profile,
+            start_mod_bisect,
+            get_mod_bisect,
+            record_bisect_result,
+            cancel_mod_bisect,
+            list_plugins,
+            search_hangar,
+            get_hangar_versions,
+            install_hangar_plugin,
             get_mc_versions,
+            get_mc_version_details,
+            get_bedrock_versions,
             get_supported_loaders,
+            get_forge_builds,
+            get_neoforge_builds,
+            get_fabric_loaders,
+            get_fabric_installers,
             list_servers,
             read_server_properties,
             update_server_properties,
+            read_bedrock_server_properties,
+            update_bedrock_server_properties,
             get_active_server,
             start_server,
             stop_server,
             read_server_config,
             update_server_config,
+            rename_server,
             open_folder,
             delete_server,
             send_mc_command,
             discord_set_server_running,
+            enable_crossplay,
+            disable_crossplay,
+            get_cache_info,
+            get_manifest_status,
+            get_network_settings,
+            update_network_settings,
+            prune_cache,
             set_idle
         ])
         .run(tauri::generate_context!())
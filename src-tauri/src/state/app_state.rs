@@ -1,5 +1,10 @@
-use crate::commands::{server_management::ActiveServer, versions_loaders::LoaderSupportCache};
-use std::{path::PathBuf, sync::{Arc, Mutex}};
+use crate::commands::{
+    creation_jobs::CreationJob,
+    manifest_cache::ManifestStatus,
+    server_management::ActiveServer,
+    versions_loaders::{LoaderSupportCache, McVersionDetails},
+};
+use std::{collections::HashMap, path::PathBuf, sync::{Arc, Mutex}};
 use tauri::AppHandle;
 
 #[derive(Default)]
@@ -7,7 +12,10 @@ pub struct AppState {
     pub app_handle: Arc<Mutex<Option<AppHandle>>>,
     pub ping_count: Arc<Mutex<u32>>,
     pub loader_cache: Arc<Mutex<Option<LoaderSupportCache>>>,
+    pub manifest_status: Arc<Mutex<ManifestStatus>>,
+    pub mc_version_cache: Arc<Mutex<HashMap<String, McVersionDetails>>>,
     pub active_server: Arc<Mutex<Option<ActiveServer>>>,
+    pub creation_jobs: Arc<Mutex<HashMap<String, CreationJob>>>,
     pub java_base_dir: Arc<Mutex<Option<PathBuf>>>,
     pub ngrok_base_dir: Arc<Mutex<Option<PathBuf>>>,
     pub playit_base_dir: Arc<Mutex<Option<PathBuf>>>,
@@ -0,0 +1,296 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use reqwest::{header, Client, StatusCode};
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+
+use crate::utils::network::mirrored;
+
+/// Shared download subsystem
+///
+/// Every download streams into `<dest>.part`, resumes an interrupted transfer
+/// with an HTTP range request, retries with exponential backoff, verifies the
+/// expected checksum and only then renames the file into place. A `.part`
+/// left by an earlier run is only resumed when a checksum can catch a file
+/// that changed upstream in the meantime, and client errors aren't retried.
+
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+// Bytes between two progress reports
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+pub const DOWNLOAD_CANCELLED: &str = "Download cancelled";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    Sha1(String),
+    Sha256(String),
+}
+
+/// Receives progress of a download and can ask for it to stop
+pub trait DownloadObserver: Send + Sync {
+    fn progress(&self, _downloaded: u64, _total: Option<u64>) {}
+
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Observer for downloads nobody is watching
+pub struct NoProgress;
+
+impl DownloadObserver for NoProgress {}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub name: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// Reports progress to the frontend as `download-progress` events
+pub struct DownloadEvents {
+    app: AppHandle,
+    name: String,
+}
+
+impl DownloadEvents {
+    pub fn new(app: AppHandle, name: impl Into<String>) -> Self {
+        Self {
+            app,
+            name: name.into(),
+        }
+    }
+}
+
+impl DownloadObserver for DownloadEvents {
+    fn progress(&self, downloaded: u64, total: Option<u64>) {
+        let _ = self.app.emit(
+            "download-progress",
+            DownloadProgress {
+                name: self.name.clone(),
+                downloaded,
+                total,
+            },
+        );
+    }
+}
+
+/// Why an attempt failed, only transient failures are retried
+enum AttemptError {
+    Transient(String),
+    Permanent(String),
+}
+
+fn transient(e: impl ToString) -> AttemptError {
+    AttemptError::Transient(e.to_string())
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Downloads `url` to `dest`, returning the size of the finished file
+pub async fn download_file(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    checksum: Option<&Checksum>,
+    observer: &dyn DownloadObserver,
+) -> Result<u64, String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let part = part_path(dest);
+
+    if checksum.is_none() {
+        fs::remove_file(&part).ok();
+    }
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        let result = match fetch_to_part(client, url, &part, observer).await {
+            Ok(()) => verify_checksum(&part, checksum).map_err(AttemptError::Transient),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) => break,
+            Err(AttemptError::Permanent(e)) if e == DOWNLOAD_CANCELLED => {
+                fs::remove_file(&part).ok();
+                return Err(e);
+            }
+            Err(AttemptError::Permanent(e)) => {
+                fs::remove_file(&part).ok();
+                return Err(format!("Failed to download {}: {}", url, e));
+            }
+            Err(AttemptError::Transient(e)) if attempt >= MAX_ATTEMPTS => {
+                fs::remove_file(&part).ok();
+                return Err(format!("Failed to download {}: {}", url, e));
+            }
+            Err(AttemptError::Transient(e)) => {
+                eprintln!("Download attempt {} of {} failed: {}", attempt, url, e);
+                attempt += 1;
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+    }
+
+    fs::rename(&part, dest).map_err(|e| e.to_string())?;
+
+    Ok(fs::metadata(dest).map(|m| m.len()).unwrap_or(0))
+}
+
+/// Streams the response into the .part file, continuing where a previous
+/// attempt stopped when the server supports range requests
+async fn fetch_to_part(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    observer: &dyn DownloadObserver,
+) -> Result<(), AttemptError> {
+    let existing = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(mirrored(url)).header(header::USER_AGENT, "Cubely");
+    if existing > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", existing));
+    }
+
+    let mut resp = request.send().await.map_err(transient)?;
+
+    // The part file already holds the whole body
+    if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(());
+    }
+
+    let status = resp.status();
+
+    // Asking again won't change a 404, but a timeout or rate limit can pass
+    if status.is_client_error()
+        && status != StatusCode::REQUEST_TIMEOUT
+        && status != StatusCode::TOO_MANY_REQUESTS
+    {
+        return Err(AttemptError::Permanent(format!("HTTP {}", status)));
+    }
+
+    if !status.is_success() {
+        return Err(transient(format!("HTTP {}", status)));
+    }
+
+    let resumed = resp.status() == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { existing } else { 0 };
+    let total = resp.content_length().map(|len| len + downloaded);
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .map_err(transient)?;
+
+    let mut reported = downloaded;
+    observer.progress(downloaded, total);
+
+    while let Some(chunk) = resp.chunk().await.map_err(transient)? {
+        if observer.is_cancelled() {
+            return Err(AttemptError::Permanent(DOWNLOAD_CANCELLED.into()));
+        }
+
+        file.write_all(&chunk).map_err(transient)?;
+        downloaded += chunk.len() as u64;
+
+        if downloaded - reported >= PROGRESS_STEP {
+            observer.progress(downloaded, total);
+            reported = downloaded;
+        }
+    }
+
+    file.flush().map_err(transient)?;
+    observer.progress(downloaded, total);
+
+    if let Some(total) = total {
+        if downloaded < total {
+            return Err(transient(format!(
+                "Connection closed after {} of {} bytes",
+                downloaded, total
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn verify_checksum(path: &Path, checksum: Option<&Checksum>) -> Result<(), String> {
+    let Some(checksum) = checksum else {
+        return Ok(());
+    };
+
+    let (expected, actual) = match checksum {
+        Checksum::Sha1(expected) => (expected, hash_file::<Sha1>(path)?),
+        Checksum::Sha256(expected) => (expected, hash_file::<Sha256>(path)?),
+    };
+
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        // A corrupt part file must not be resumed
+        fs::remove_file(path).ok();
+        return Err(format!("Checksum mismatch: expected {}, got {}", expected, actual));
+    }
+
+    Ok(())
+}
+
+/// Hex digest of a file
+pub fn hash_file<D: Digest>(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = D::new();
+    let mut buf = [0u8; 64 * 1024];
+
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Maven repositories publish `<artifact>.sha1` next to every artifact
+pub async fn fetch_maven_sha1(client: &Client, artifact_url: &str) -> Option<Checksum> {
+    let text = client
+        .get(format!("{}.sha1", mirrored(artifact_url)))
+        .header(header::USER_AGENT, "Cubely")
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?
+        .text()
+        .await
+        .ok()?;
+
+    // Some repositories append the file name after the hash
+    let hash = text.split_whitespace().next()?;
+
+    (hash.len() == 40).then(|| Checksum::Sha1(hash.to_string()))
+}
@@ -1 +1,3 @@
 pub mod path;
+pub mod download;
+pub mod network;
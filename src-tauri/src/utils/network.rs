@@ -0,0 +1,231 @@
+use std::{collections::HashMap, fs, sync::RwLock};
+
+use once_cell::sync::Lazy;
+use reqwest::{Client, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::path::network_settings_path;
+
+/// Endpoint table, mirrors and proxy
+///
+/// Every remote service Cubely talks to is listed in `Endpoint`. A mirror
+/// replaces an endpoint's base URL, both for URLs built here and for URLs
+/// handed to us by manifests (see `mirrored`). All HTTP clients come from
+/// `http_client`, which applies the configured HTTP or SOCKS proxy.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Endpoint {
+    MojangMeta,
+    MojangPistonMeta,
+    MojangPistonData,
+    MojangLauncher,
+    BedrockLinks,
+    BedrockDownloads,
+    FabricMeta,
+    FabricMaven,
+    ForgeMaven,
+    ForgeFiles,
+    NeoForgeMaven,
+    PaperApi,
+    PaperDownloads,
+    Adoptium,
+    AdoptiumDownloads,
+    Ngrok,
+    PlayitApi,
+    PlayitDownloads,
+    Geyser,
+    Modrinth,
+    ModrinthCdn,
+    CurseForgeApi,
+    CurseForgeCdn,
+    Hangar,
+    HangarCdn,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 25] = [
+        Endpoint::MojangMeta,
+        Endpoint::MojangPistonMeta,
+        Endpoint::MojangPistonData,
+        Endpoint::MojangLauncher,
+        Endpoint::BedrockLinks,
+        Endpoint::BedrockDownloads,
+        Endpoint::FabricMeta,
+        Endpoint::FabricMaven,
+        Endpoint::ForgeMaven,
+        Endpoint::ForgeFiles,
+        Endpoint::NeoForgeMaven,
+        Endpoint::PaperApi,
+        Endpoint::PaperDownloads,
+        Endpoint::Adoptium,
+        Endpoint::AdoptiumDownloads,
+        Endpoint::Ngrok,
+        Endpoint::PlayitApi,
+        Endpoint::PlayitDownloads,
+        Endpoint::Geyser,
+        Endpoint::Modrinth,
+        Endpoint::ModrinthCdn,
+        Endpoint::CurseForgeApi,
+        Endpoint::CurseForgeCdn,
+        Endpoint::Hangar,
+        Endpoint::HangarCdn,
+    ];
+
+    /// Official base URL, without a trailing slash
+    pub fn default_url(self) -> &'static str {
+        match self {
+            Endpoint::MojangMeta => "https://launchermeta.mojang.com",
+            Endpoint::MojangPistonMeta => "https://piston-meta.mojang.com",
+            Endpoint::MojangPistonData => "https://piston-data.mojang.com",
+            Endpoint::MojangLauncher => "https://launcher.mojang.com",
+            Endpoint::BedrockLinks => "https://net-secondary.web.minecraft-services.net",
+            Endpoint::BedrockDownloads => "https://www.minecraft.net/bedrockdedicatedserver",
+            Endpoint::FabricMeta => "https://meta.fabricmc.net",
+            Endpoint::FabricMaven => "https://maven.fabricmc.net",
+            Endpoint::ForgeMaven => "https://maven.minecraftforge.net",
+            Endpoint::ForgeFiles => "https://files.minecraftforge.net",
+            Endpoint::NeoForgeMaven => "https://maven.neoforged.net/releases",
+            Endpoint::PaperApi => "https://fill.papermc.io",
+            Endpoint::PaperDownloads => "https://fill-data.papermc.io",
+            Endpoint::Adoptium => "https://api.adoptium.net",
+            Endpoint::AdoptiumDownloads => "https://github.com/adoptium",
+            Endpoint::Ngrok => "https://bin.equinox.io",
+            Endpoint::PlayitApi => "https://api.playit.gg",
+            Endpoint::PlayitDownloads => "https://github.com/playit-cloud/playit-agent/releases",
+            Endpoint::Geyser => "https://download.geysermc.org",
+            Endpoint::Modrinth => "https://api.modrinth.com",
+            Endpoint::ModrinthCdn => "https://cdn.modrinth.com",
+            Endpoint::CurseForgeApi => "https://api.curseforge.com",
+            Endpoint::CurseForgeCdn => "https://edge.forgecdn.net",
+            Endpoint::Hangar => "https://hangar.papermc.io",
+            Endpoint::HangarCdn => "https://hangarcdn.papermc.io",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxySettings {
+    pub url: String, // http://, https://, socks5:// or socks5h://
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub no_proxy: Option<String>, // comma separated hosts, localhost is always direct
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    #[serde(default)]
+    pub mirrors: HashMap<Endpoint, String>,
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    #[serde(default)]
+    pub curseforge_api_key: Option<String>, // CurseForge's API refuses requests without one
+}
+
+static SETTINGS: Lazy<RwLock<NetworkSettings>> = Lazy::new(|| RwLock::new(load_settings()));
+
+fn load_settings() -> NetworkSettings {
+    fs::read_to_string(network_settings_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn network_settings() -> NetworkSettings {
+    SETTINGS.read().unwrap().clone()
+}
+
+/// Checks the proxy, saves the settings and applies them to new clients
+pub fn save_network_settings(mut settings: NetworkSettings) -> Result<(), String> {
+    for url in settings.mirrors.values_mut() {
+        *url = url.trim().trim_end_matches('/').to_string();
+    }
+    settings.mirrors.retain(|_, url| !url.is_empty());
+
+    settings.curseforge_api_key = settings
+        .curseforge_api_key
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty());
+
+    if let Some(proxy) = &settings.proxy {
+        build_proxy(proxy)?;
+    }
+
+    let path = network_settings_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    fs::write(&path, serde_json::to_string_pretty(&settings).unwrap())
+        .map_err(|e| format!("Failed to save network settings: {}", e))?;
+
+    *SETTINGS.write().unwrap() = settings;
+
+    Ok(())
+}
+
+/// Base URL of `endpoint`, the mirror when one is configured
+pub fn endpoint(endpoint: Endpoint) -> String {
+    SETTINGS
+        .read()
+        .unwrap()
+        .mirrors
+        .get(&endpoint)
+        .cloned()
+        .unwrap_or_else(|| endpoint.default_url().to_string())
+}
+
+/// Points a URL at the configured mirror of the endpoint it belongs to.
+/// Used for URLs that come from manifests rather than the table.
+pub fn mirrored(url: &str) -> String {
+    let settings = SETTINGS.read().unwrap();
+
+    // The most specific base wins, e.g. Playit's GitHub path over another GitHub one
+    settings
+        .mirrors
+        .iter()
+        .filter_map(|(endpoint, mirror)| {
+            let rest = url.strip_prefix(endpoint.default_url())?;
+            (rest.is_empty() || rest.starts_with('/'))
+                .then(|| (endpoint.default_url().len(), format!("{}{}", mirror, rest)))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, url)| url)
+        .unwrap_or_else(|| url.to_string())
+}
+
+fn build_proxy(settings: &ProxySettings) -> Result<Proxy, String> {
+    let mut proxy =
+        Proxy::all(settings.url.trim()).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+
+    if let Some(username) = &settings.username {
+        proxy = proxy.basic_auth(username, settings.password.as_deref().unwrap_or(""));
+    }
+
+    let no_proxy = match &settings.no_proxy {
+        Some(hosts) if !hosts.trim().is_empty() => format!("localhost,127.0.0.1,::1,{}", hosts),
+        _ => "localhost,127.0.0.1,::1".to_string(),
+    };
+
+    Ok(proxy.no_proxy(NoProxy::from_string(&no_proxy)))
+}
+
+/// HTTP client honouring the proxy settings
+pub fn http_client() -> Client {
+    let mut builder = Client::builder().user_agent("Cubely");
+
+    if let Some(settings) = &SETTINGS.read().unwrap().proxy {
+        match build_proxy(settings) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => eprintln!("Ignoring proxy settings: {}", e),
+        }
+    }
+
+    builder.build().unwrap_or_else(|e| {
+        eprintln!("Failed to build HTTP client: {}", e);
+        Client::new()
+    })
+}
@@ -1,4 +1,4 @@
-use std::{fs, path::PathBuf};
+use std::{fs, path::{Path, PathBuf}};
 
 /// Returns the base directory where all Cubely servers are stored.
 ///
@@ -49,3 +49,141 @@ pub fn cleanup_empty_parent_dir(parent: &PathBuf) {
         }
     }
 }
+
+/// Returns the directory holding Cubely's shared download cache.
+///
+/// Lives next to the servers directory, e.g. `~/.local/share/Cubely/cache` on Linux.
+/// Like `servers_dir`, this only resolves the path.
+pub fn cache_dir() -> PathBuf {
+    let mut dir = dirs::data_dir().expect("Failed to get data dir");
+    dir.push("Cubely");
+    dir.push("cache");
+    dir
+}
+
+/// Returns the path of the network settings file (mirrors and proxy).
+pub fn network_settings_path() -> PathBuf {
+    let mut path = dirs::data_dir().expect("Failed to get data dir");
+    path.push("Cubely");
+    path.push("network.json");
+    path
+}
+
+/// Returns the directory where server backups are kept, next to `servers_dir`.
+pub fn backups_dir() -> PathBuf {
+    let mut dir = dirs::data_dir().expect("Failed to get data dir");
+    dir.push("Cubely");
+    dir.push("backups");
+    dir
+}
+
+/// Recursively copies a directory and everything in it.
+pub fn copy_dir_all(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| e.to_string())?;
+
+    for entry in fs::read_dir(from).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let target = to.join(entry.file_name());
+
+        if entry.file_type().map_err(|e| e.to_string())?.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+// Device names Windows refuses as file or folder names
+const RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+const MAX_SLUG_LEN: usize = 48;
+
+/// Turns a display name into a folder name that is safe on every OS. [Ex: "My Server!" -> "my-server"]
+///
+/// Only lowercase ASCII letters, digits, `-` and `_` survive, so separators,
+/// `..` and other special characters can't escape `servers_dir()`.
+pub fn server_slug(name: &str) -> String {
+    let mut slug = String::new();
+
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let mut slug: String = slug.trim_matches('-').chars().take(MAX_SLUG_LEN).collect();
+    slug = slug.trim_end_matches('-').to_string();
+
+    if slug.is_empty() || RESERVED_NAMES.contains(&slug.as_str()) {
+        slug = format!("server-{}", slug).trim_end_matches('-').to_string();
+    }
+
+    slug
+}
+
+/// Returns a directory under `servers_dir()` for `name` that is not taken yet.
+/// [Ex: "my-server", then "my-server-2" ...]
+///
+/// `current` is the server's own directory when renaming, so keeping the slug is allowed.
+pub fn unique_server_dir(name: &str, current: Option<&Path>) -> PathBuf {
+    let base = servers_dir();
+    let slug = server_slug(name);
+    let mut candidate = base.join(&slug);
+    let mut n = 2;
+
+    while candidate.exists() && Some(candidate.as_path()) != current {
+        candidate = base.join(format!("{}-{}", slug, n));
+        n += 1;
+    }
+
+    candidate
+}
+
+/// Returns the directory holding saved server templates, next to `servers_dir`.
+pub fn templates_dir() -> PathBuf {
+    let mut dir = dirs::data_dir().expect("Failed to get data dir");
+    dir.push("Cubely");
+    dir.push("templates");
+    dir
+}
+
+/// Returns the directory exported modpacks are written to, next to `servers_dir`.
+pub fn exports_dir() -> PathBuf {
+    let mut dir = dirs::data_dir().expect("Failed to get data dir");
+    dir.push("Cubely");
+    dir.push("exports");
+    dir
+}
+
+/// Like `copy_dir_all`, but skips the top-level entries named in `exclude`. [Ex: "world", "logs"]
+pub fn copy_dir_excluding(from: &Path, to: &Path, exclude: &[String]) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| e.to_string())?;
+
+    for entry in fs::read_dir(from).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if exclude.contains(&name) {
+            continue;
+        }
+
+        let target = to.join(&name);
+
+        if entry.path().is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+        }
+    }
+
+    Ok(())
+}